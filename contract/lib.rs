@@ -42,115 +42,2704 @@ mod azns_router {
     }
 }
 
-#[openbrush::implementation(PSP34)]
+mod psp22 {
+    use openbrush::traits::{AccountId, Balance};
+    use scale_info::prelude::string::String;
+    use scale_info::prelude::vec::Vec;
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum PSP22Error {
+        /// Custom error type for cases if writer of traits added own restrictions
+        Custom(String),
+        /// Returned if not enough balance to fulfill a request is available.
+        InsufficientBalance,
+        /// Returned if not enough allowance to fulfill a request is available.
+        InsufficientAllowance,
+        /// Returned if recipient's address is zero.
+        ZeroRecipientAddress,
+        /// Returned if sender's address is zero.
+        ZeroSenderAddress,
+        /// Returned if safe transfer check fails
+        SafeTransferCheckFailed(String),
+    }
+
+    #[ink::trait_definition]
+    pub trait PSP22 {
+        #[ink(message, selector = 0x6568382f)]
+        fn balance_of(&self, owner: AccountId) -> Balance;
+
+        #[ink(message, selector = 0xdb20f9f5)]
+        fn transfer(
+            &mut self,
+            to: AccountId,
+            value: Balance,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error>;
+
+        #[ink(message, selector = 0x54b3c76e)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error>;
+    }
+}
+
+/// Verification of sr25519 signatures, as made by substrate accounts.
+#[cfg(feature = "attestations")]
+mod sr25519 {
+    /// Whether `signature` over `message` was made by `public_key`.
+    ///
+    /// ink 4 does not wrap the unstable `sr25519_verify` host function of
+    /// pallet-contracts, so it is imported directly. Chains only accept code
+    /// importing it with the unstable interface enabled, hence the feature.
+    #[cfg(not(feature = "std"))]
+    pub fn verify(signature: &[u8; 64], message: &[u8], public_key: &[u8; 32]) -> bool {
+        #[link(wasm_import_module = "seal0")]
+        extern "C" {
+            fn sr25519_verify(
+                signature_ptr: *const u8,
+                public_key_ptr: *const u8,
+                message_len: u32,
+                message_ptr: *const u8,
+            ) -> u32;
+        }
+        // SAFETY: the host only reads 64 bytes of signature, 32 bytes of public key
+        // and `message_len` bytes of message.
+        unsafe {
+            sr25519_verify(
+                signature.as_ptr(),
+                public_key.as_ptr(),
+                message.len() as u32,
+                message.as_ptr(),
+            ) == 0
+        }
+    }
+
+    /// Whether `signature` over `message` was made by `public_key`.
+    #[cfg(feature = "std")]
+    pub fn verify(signature: &[u8; 64], message: &[u8], public_key: &[u8; 32]) -> bool {
+        let Ok(public_key) = schnorrkel::PublicKey::from_bytes(public_key) else {
+            return false;
+        };
+        let Ok(signature) = schnorrkel::Signature::from_bytes(signature) else {
+            return false;
+        };
+        public_key
+            .verify_simple(b"substrate", message, &signature)
+            .is_ok()
+    }
+}
+
+#[openbrush::implementation(PSP34, PSP34Metadata, Ownable)]
 #[openbrush::contract]
 pub mod handshake {
+    use ink::prelude::string::ToString;
+    use ink::storage::Mapping;
+    use openbrush::modifiers;
     use openbrush::traits::Storage;
     use openbrush::traits::String;
 
     use crate::azns_router::{AznsContract, Error as AznsRouterError};
+    use crate::psp22::{PSP22Error, PSP22};
+    #[cfg(feature = "attestations")]
+    use crate::sr25519;
+    #[cfg(feature = "attestations")]
+    use scale::Encode;
+
+    /// Maximum number of entries returned by the paginated getters.
+    pub const MAX_PAGE_SIZE: u32 = 100;
+
+    /// Maximum number of attestations accepted by a single call.
+    #[cfg(feature = "attestations")]
+    pub const MAX_BATCH_SIZE: u32 = 50;
+
+    /// Basis points of a whole, the upper bound of the organizer fee.
+    pub const BASIS_POINTS: u16 = 10_000;
+
+    /// Minimum time in milliseconds between the start of settlement and the
+    /// claim deadline.
+    pub const MIN_CLAIM_WINDOW: Timestamp = 7 * 24 * 60 * 60 * 1000;
+
+    /// Maximum length in bytes of the note attached to a handshake.
+    pub const MAX_NOTE_LENGTH: u32 = 64;
+
+    /// Maximum length in bytes of the encrypted contact attached to a handshake.
+    pub const MAX_CONTACT_LENGTH: u32 = 256;
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum HandshakeError {
+        /// Transferred value is below the ticket price or funded amount.
+        InsufficientPayment,
+        /// Account did not sign up or no longer holds its ticket.
+        NotRegistered,
+        /// Account already signed up.
+        AlreadyRegistered,
+        /// Contract balance cannot cover the requested transfer.
+        InsufficientFunds,
+        /// Native transfer was rejected by the chain.
+        TransferFailed,
+        /// Caller is not the owner of the contract.
+        NotOwner,
+        /// Event does not accept this call anymore.
+        EventClosed,
+        /// Call is not allowed in the current phase of the event.
+        WrongPhase,
+        /// Attendees can still claim their cashback.
+        ClaimWindowOpen,
+        /// Phase timestamps are not in increasing order.
+        InvalidSchedule,
+        /// Tier thresholds are not in increasing order.
+        InvalidThresholds,
+        /// Split rule does not require any handshakes.
+        InvalidSplitRule,
+        /// Organizer fee exceeds [`BASIS_POINTS`].
+        InvalidFee,
+        /// Attestation is not signed by both of its parties.
+        InvalidSignature,
+        /// More than `MAX_BATCH_SIZE` attestations were submitted at once.
+        BatchTooLarge,
+        /// Caller tried to shake their own hand.
+        SelfHandshake,
+        /// Caller already sent a handshake to this account.
+        AlreadyRequested,
+        /// Both accounts already share a confirmed handshake.
+        AlreadyConnected,
+        /// There is no pending handshake to confirm.
+        NoPendingHandshake,
+        /// Note or contact exceed [`MAX_NOTE_LENGTH`] or [`MAX_CONTACT_LENGTH`].
+        MetadataTooLarge,
+        /// Error of the staking token.
+        PSP22Error(PSP22Error),
+        /// Error of the ticket NFT.
+        PSP34Error(PSP34Error),
+        /// Error of the ownership management.
+        OwnableError(OwnableError),
+    }
+
+    /// Phases of an event, in the order they are passed through.
+    #[derive(
+        Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, scale::Encode, scale::Decode,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum Phase {
+        /// Attendees sign up and receive their tickets.
+        #[default]
+        Registration,
+        /// Attendees exchange handshakes.
+        Networking,
+        /// Attendees collect their cashback.
+        Settlement,
+        /// Nothing can be changed anymore.
+        Closed,
+    }
+
+    /// Achievement of an attendee, earned by the number of confirmed handshakes.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Tier {
+        Bronze,
+        Silver,
+        Gold,
+    }
+
+    /// How the sponsor pool is split among the attendees.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum SplitRule {
+        /// By each attendee's share of all confirmed handshakes.
+        #[default]
+        Proportional,
+        /// Equally among the attendees with at least this many confirmed handshakes.
+        MinHandshakes(u32),
+    }
+
+    /// Handshake between `a` and `b`, signed off-chain by both of them.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct Attestation {
+        pub a: AccountId,
+        pub b: AccountId,
+        /// Signatures of both parties over [`Handshake::attestation_message`].
+        pub signature_a: [u8; 64],
+        pub signature_b: [u8; 64],
+    }
+
+    /// What an attendee attaches to the handshake they send.
+    #[derive(Debug, Clone, Default, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct HandshakeMetadata {
+        /// Short note or tag, e.g. "hiring" or "talk follow-up".
+        pub note: String,
+        /// Contact details encrypted to the counterparty's [`Handshake::contact_key`],
+        /// opaque to the contract.
+        pub contact: Option<Vec<u8>>,
+    }
+
+    impl Tier {
+        /// Value of the `tier` attribute of a ticket.
+        fn name(&self) -> &'static str {
+            match self {
+                Tier::Bronze => "bronze",
+                Tier::Silver => "silver",
+                Tier::Gold => "gold",
+            }
+        }
+    }
+
+    impl From<PSP22Error> for HandshakeError {
+        fn from(error: PSP22Error) -> Self {
+            HandshakeError::PSP22Error(error)
+        }
+    }
+
+    impl From<PSP34Error> for HandshakeError {
+        fn from(error: PSP34Error) -> Self {
+            HandshakeError::PSP34Error(error)
+        }
+    }
+
+    impl From<OwnableError> for HandshakeError {
+        fn from(error: OwnableError) -> Self {
+            match error {
+                OwnableError::CallerIsNotOwner => HandshakeError::NotOwner,
+                error => HandshakeError::OwnableError(error),
+            }
+        }
+    }
+
+    /// Emitted when an account signs up and receives its ticket.
+    #[ink(event)]
+    pub struct SignedUp {
+        #[ink(topic)]
+        account: AccountId,
+        id: Id,
+        value: Balance,
+    }
+
+    /// Emitted when a sponsor adds to the sponsor pool.
+    #[ink(event)]
+    pub struct PoolFunded {
+        #[ink(topic)]
+        sponsor: AccountId,
+        value: Balance,
+    }
+
+    /// Emitted when `from` asks `to` for a handshake.
+    #[ink(event)]
+    pub struct HandshakeRequested {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+    }
+
+    /// Emitted when `to` confirms the handshake requested by `from`.
+    #[ink(event)]
+    pub struct HandshakeConfirmed {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+    }
+
+    /// Emitted when `from` withdraws its pending handshake request to `to`.
+    #[ink(event)]
+    pub struct HandshakeCancelled {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+    }
+
+    /// Emitted when `to` declines the pending handshake request of `from`.
+    #[ink(event)]
+    pub struct HandshakeRejected {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+    }
+
+    /// Emitted when an account's cashback is paid out.
+    #[ink(event)]
+    pub struct PaidOut {
+        #[ink(topic)]
+        account: AccountId,
+        value: Balance,
+    }
+
+    /// Emitted when the owner withdraws the organizer share.
+    #[ink(event)]
+    pub struct OrganizerShareWithdrawn {
+        #[ink(topic)]
+        owner: AccountId,
+        value: Balance,
+    }
+
+    /// Emitted when the owner sweeps the balance left after the event closed.
+    #[ink(event)]
+    pub struct UnclaimedSwept {
+        #[ink(topic)]
+        owner: AccountId,
+        value: Balance,
+    }
+
+    /// Emitted when a ticket is upgraded to an achievement tier.
+    #[ink(event)]
+    pub struct TierAwarded {
+        #[ink(topic)]
+        account: AccountId,
+        tier: Tier,
+    }
+
+    #[ink(storage)]
+    #[derive(Default, Storage)]
+    pub struct Handshake {
+        #[storage_field]
+        psp34: psp34::Data,
+        #[storage_field]
+        metadata: metadata::Data,
+        #[storage_field]
+        ownable: ownable::Data,
+        /// Name of the event, also the name of the ticket collection.
+        name: String,
+        /// Id of the next ticket, minted as `Id::U32`.
+        next_id: u32,
+        ticket_price: Balance,
+        /// PSP22 token stakes and cashbacks are paid in, native balance if `None`.
+        token: Option<AccountId>,
+        phase: Phase,
+        /// Timestamps at which the phases start, `0` while not scheduled.
+        networking_starts: Timestamp,
+        settlement_starts: Timestamp,
+        closes_at: Timestamp,
+        /// Handshakes needed for bronze, silver and gold, `0` while not configured.
+        tier_thresholds: (u32, u32, u32),
+        /// Sum of all ticket payments, shared among the attendees.
+        pool: Balance,
+        /// Sum of all sponsor contributions, split among the attendees by `split_rule`.
+        sponsor_pool: Balance,
+        split_rule: SplitRule,
+        /// Attendees reaching the handshakes of a `MinHandshakes` split rule.
+        num_qualified: u32,
+        /// Share of both pools kept by the organizer, in basis points.
+        organizer_fee: u16,
+        total_paid_out: Balance,
+        /// Signed up accounts in signup order.
+        accounts: Mapping<u32, AccountId>,
+        num_accounts: u32,
+        /// Ticket minted to each signed up account.
+        tickets: Mapping<AccountId, Id>,
+        stakes: Mapping<AccountId, Balance>,
+        /// Pending handshake `(from, to)` mapped to its position in the incoming list of `to`.
+        pending: Mapping<(AccountId, AccountId), u32>,
+        incoming: Mapping<(AccountId, u32), AccountId>,
+        num_incoming: Mapping<AccountId, u32>,
+        /// Pending handshake `(from, to)` mapped to its position in the outgoing list of `from`.
+        outgoing_index: Mapping<(AccountId, AccountId), u32>,
+        outgoing: Mapping<(AccountId, u32), AccountId>,
+        num_outgoing: Mapping<AccountId, u32>,
+        /// Confirmed handshakes, keyed by the ordered pair of both parties.
+        connected: Mapping<(AccountId, AccountId), ()>,
+        /// Metadata attached by `from` to its handshake with `to`, keyed by `(from, to)`.
+        notes: Mapping<(AccountId, AccountId), HandshakeMetadata>,
+        /// Public keys attendees receive encrypted contacts with.
+        contact_keys: Mapping<AccountId, [u8; 32]>,
+        connections: Mapping<(AccountId, u32), AccountId>,
+        num_connections: Mapping<AccountId, u32>,
+        num_confirmed: u32,
+        /// Accounts with confirmed handshakes, ordered by their number, most first.
+        ranking: Mapping<u32, AccountId>,
+        ranks: Mapping<AccountId, u32>,
+        /// First position in `ranking` of the accounts with a number of handshakes.
+        rank_starts: Mapping<u32, u32>,
+        num_ranked: u32,
+        /// Accounts which claimed their cashback.
+        claimed: Mapping<AccountId, ()>,
+        num_claimed: u32,
+        /// Accounts whose ticket recorded the networking result.
+        settled: Mapping<AccountId, ()>,
+    }
+
+    impl Handshake {
+        #[ink(constructor)]
+        pub fn new(ticket_price: Balance, name: String, symbol: String) -> Self {
+            Self::with_owner(Self::env().caller(), ticket_price, name, symbol, None)
+        }
+
+        /// Creates an event staking in the PSP22 `token` instead of native balance.
+        #[ink(constructor)]
+        pub fn with_token(
+            ticket_price: Balance,
+            name: String,
+            symbol: String,
+            token: AccountId,
+        ) -> Self {
+            Self::with_owner(
+                Self::env().caller(),
+                ticket_price,
+                name,
+                symbol,
+                Some(token),
+            )
+        }
+
+        /// Creates an event organized by `owner`, used when instantiated by the registry.
+        #[ink(constructor)]
+        pub fn with_owner(
+            owner: AccountId,
+            ticket_price: Balance,
+            name: String,
+            symbol: String,
+            token: Option<AccountId>,
+        ) -> Self {
+            let mut instance = Self {
+                ticket_price,
+                token,
+                name: name.clone(),
+                ..Default::default()
+            };
+            ownable::Internal::_init_with_owner(&mut instance, owner);
+            let collection_id = Id::Bytes(
+                <AccountId as AsRef<[u8; 32]>>::as_ref(&Self::env().account_id()).to_vec(),
+            );
+            metadata::Internal::_set_attribute(
+                &mut instance,
+                collection_id.clone(),
+                String::from("name"),
+                name,
+            );
+            metadata::Internal::_set_attribute(
+                &mut instance,
+                collection_id,
+                String::from("symbol"),
+                symbol,
+            );
+            instance
+        }
+
+        #[ink(message, payable)]
+        pub fn signup(&mut self) -> Result<(), HandshakeError> {
+            self.ensure_phase(Phase::Registration)?;
+            let caller = Self::env().caller();
+            if self.tickets.contains(caller) {
+                return Err(HandshakeError::AlreadyRegistered);
+            }
+            let stake = self.ticket_price;
+            self.collect(caller, stake)?;
+            let id = Id::U32(self.next_id);
+            psp34::Internal::_mint_to(self, caller, id.clone())?;
+            metadata::Internal::_set_attribute(
+                self,
+                id.clone(),
+                String::from("event"),
+                self.name.clone(),
+            );
+            metadata::Internal::_set_attribute(
+                self,
+                id.clone(),
+                String::from("tier"),
+                String::from("attendee"),
+            );
+            self.next_id += 1;
+            self.accounts.insert(self.num_accounts, &caller);
+            self.num_accounts += 1;
+            self.pool += stake;
+            self.stakes.insert(caller, &stake);
+            self.tickets.insert(caller, &id);
+
+            self.env().emit_event(SignedUp {
+                account: caller,
+                id,
+                value: stake,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_registered(&self, account: AccountId) -> bool {
+            self.tickets.contains(account)
+        }
+
+        /// Ticket minted to `account` at signup.
+        #[ink(message)]
+        pub fn ticket_of(&self, account: AccountId) -> Option<Id> {
+            self.tickets.get(account)
+        }
+
+        /// Ticket price `account` paid at signup.
+        #[ink(message)]
+        pub fn stake_of(&self, account: AccountId) -> Balance {
+            self.stakes.get(account).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        pub fn num_accounts(&self) -> Result<u32, HandshakeError> {
+            Ok(self.num_accounts)
+        }
+
+        /// Signed up accounts, `limit` entries starting at `offset`.
+        #[ink(message)]
+        pub fn accounts(&self, offset: u32, limit: u32) -> Vec<AccountId> {
+            Self::page(self.num_accounts, offset, limit, |index| {
+                self.accounts.get(index)
+            })
+        }
+
+        #[ink(message)]
+        pub fn handshake(&mut self, other: AccountId) -> Result<(), HandshakeError> {
+            let executive = Self::env().caller();
+            self.ensure_phase(Phase::Networking)?;
+            if executive == other {
+                return Err(HandshakeError::SelfHandshake);
+            }
+            if !self.is_attendee(executive) || !self.is_attendee(other) {
+                return Err(HandshakeError::NotRegistered);
+            }
+            if self.is_connected(executive, other) {
+                return Err(HandshakeError::AlreadyConnected);
+            }
+            if self.pending.contains((executive, other)) {
+                return Err(HandshakeError::AlreadyRequested);
+            }
+            // the other side already asked for this handshake, so it is mutual
+            if self.pending.contains((other, executive)) {
+                return self.confirm_handshake(other);
+            }
+            let index = self.num_incoming.get(other).unwrap_or_default();
+            self.incoming.insert((other, index), &executive);
+            self.num_incoming.insert(other, &(index + 1));
+            self.pending.insert((executive, other), &index);
+            let index = self.num_outgoing.get(executive).unwrap_or_default();
+            self.outgoing.insert((executive, index), &other);
+            self.num_outgoing.insert(executive, &(index + 1));
+            self.outgoing_index.insert((executive, other), &index);
+            self.env().emit_event(HandshakeRequested {
+                from: executive,
+                to: other,
+            });
+            Ok(())
+        }
+
+        /// Sends or confirms a handshake with `other` like [`Handshake::handshake`],
+        /// attaching `metadata` to it.
+        #[ink(message)]
+        pub fn handshake_with_metadata(
+            &mut self,
+            other: AccountId,
+            metadata: HandshakeMetadata,
+        ) -> Result<(), HandshakeError> {
+            let contact_length = metadata.contact.as_ref().map_or(0, Vec::len);
+            if metadata.note.len() > MAX_NOTE_LENGTH as usize
+                || contact_length > MAX_CONTACT_LENGTH as usize
+            {
+                return Err(HandshakeError::MetadataTooLarge);
+            }
+            self.handshake(other)?;
+            self.notes.insert((Self::env().caller(), other), &metadata);
+            Ok(())
+        }
+
+        /// Metadata `from` attached to its handshake with `to`.
+        #[ink(message)]
+        pub fn handshake_metadata(
+            &self,
+            from: AccountId,
+            to: AccountId,
+        ) -> Option<HandshakeMetadata> {
+            self.notes.get((from, to))
+        }
+
+        /// Publishes the caller's public key for contacts encrypted to it, an
+        /// x25519 key the dapp keeps the secret of.
+        #[ink(message)]
+        pub fn set_contact_key(&mut self, key: [u8; 32]) -> Result<(), HandshakeError> {
+            let caller = Self::env().caller();
+            if !self.is_registered(caller) {
+                return Err(HandshakeError::NotRegistered);
+            }
+            self.contact_keys.insert(caller, &key);
+            Ok(())
+        }
+
+        /// Public key `account` receives encrypted contacts with.
+        #[ink(message)]
+        pub fn contact_key(&self, account: AccountId) -> Option<[u8; 32]> {
+            self.contact_keys.get(account)
+        }
+
+        /// Confirms a pending handshake sent by `from` to the caller.
+        #[ink(message)]
+        pub fn confirm_handshake(&mut self, from: AccountId) -> Result<(), HandshakeError> {
+            let caller = Self::env().caller();
+            self.ensure_phase(Phase::Networking)?;
+            if !self.pending.contains((from, caller)) {
+                return Err(HandshakeError::NoPendingHandshake);
+            }
+            // either side may have handed its ticket on since the request
+            if !self.is_attendee(from) || !self.is_attendee(caller) {
+                return Err(HandshakeError::NotRegistered);
+            }
+            self.remove_pending(from, caller);
+            self.connect(from, caller);
+            Ok(())
+        }
+
+        /// Withdraws the caller's pending handshake request to `other`.
+        #[ink(message)]
+        pub fn cancel_handshake(&mut self, other: AccountId) -> Result<(), HandshakeError> {
+            let caller = Self::env().caller();
+            self.ensure_phase(Phase::Networking)?;
+            if !self.remove_pending(caller, other) {
+                return Err(HandshakeError::NoPendingHandshake);
+            }
+            self.notes.remove((caller, other));
+            self.env().emit_event(HandshakeCancelled {
+                from: caller,
+                to: other,
+            });
+            Ok(())
+        }
+
+        /// Declines a pending handshake sent by `from` to the caller.
+        #[ink(message)]
+        pub fn reject_handshake(&mut self, from: AccountId) -> Result<(), HandshakeError> {
+            let caller = Self::env().caller();
+            self.ensure_phase(Phase::Networking)?;
+            if !self.remove_pending(from, caller) {
+                return Err(HandshakeError::NoPendingHandshake);
+            }
+            self.notes.remove((from, caller));
+            self.env()
+                .emit_event(HandshakeRejected { from, to: caller });
+            Ok(())
+        }
+
+        /// Confirms handshakes signed off-chain by both parties, so a relayer or
+        /// the organizer can submit them in one call. Returns the number of new
+        /// handshakes, pairs which are already connected are skipped.
+        #[cfg(feature = "attestations")]
+        #[ink(message)]
+        pub fn submit_attestations(
+            &mut self,
+            attestations: Vec<Attestation>,
+        ) -> Result<u32, HandshakeError> {
+            self.ensure_phase(Phase::Networking)?;
+            if attestations.len() > MAX_BATCH_SIZE as usize {
+                return Err(HandshakeError::BatchTooLarge);
+            }
+            let mut confirmed = 0;
+            for Attestation {
+                a,
+                b,
+                signature_a,
+                signature_b,
+            } in attestations
+            {
+                if a == b {
+                    return Err(HandshakeError::SelfHandshake);
+                }
+                if !self.is_attendee(a) || !self.is_attendee(b) {
+                    return Err(HandshakeError::NotRegistered);
+                }
+                let message = self.attestation_message(a, b);
+                let signed_by = |signature: &[u8; 64], account: &AccountId| {
+                    sr25519::verify(
+                        signature,
+                        &message,
+                        <AccountId as AsRef<[u8; 32]>>::as_ref(account),
+                    )
+                };
+                if !signed_by(&signature_a, &a) || !signed_by(&signature_b, &b) {
+                    return Err(HandshakeError::InvalidSignature);
+                }
+                if self.is_connected(a, b) {
+                    continue;
+                }
+                self.remove_pending(a, b);
+                self.remove_pending(b, a);
+                self.connect(a, b);
+                confirmed += 1;
+            }
+            Ok(confirmed)
+        }
+
+        /// Message both parties sign to attest their handshake at this event.
+        ///
+        /// The SCALE encoded contract address and ordered pair of accounts, wrapped
+        /// in `<Bytes>` the way `signRaw` of the browser extensions does.
+        #[cfg(feature = "attestations")]
+        #[ink(message)]
+        pub fn attestation_message(&self, a: AccountId, b: AccountId) -> Vec<u8> {
+            let payload = (self.env().account_id(), Self::pair(a, b)).encode();
+            [b"<Bytes>".as_slice(), &payload, b"</Bytes>"].concat()
+        }
+
+        /// Number of confirmed, mutual handshakes.
+        #[ink(message)]
+        pub fn num_handshakes(&self) -> Result<u32, HandshakeError> {
+            Ok(self.num_confirmed)
+        }
+
+        /// Number of distinct accounts with at least one confirmed handshake.
+        #[ink(message)]
+        pub fn num_active_accounts(&self) -> Result<u32, HandshakeError> {
+            // every account joins the ranking with its first confirmed handshake
+            Ok(self.num_ranked)
+        }
+
+        /// First [`MAX_PAGE_SIZE`] pending handshakes sent to the caller, use
+        /// [`Handshake::open_handshakes_of`] to page through all of them.
+        #[ink(message)]
+        pub fn open_handshakes(&self) -> Result<Vec<AccountId>, HandshakeError> {
+            Ok(self.open_handshakes_of(Self::env().caller(), 0, MAX_PAGE_SIZE))
+        }
+
+        /// Pending handshakes sent to `account`, `limit` entries starting at `offset`.
+        #[ink(message)]
+        pub fn open_handshakes_of(
+            &self,
+            account: AccountId,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<AccountId> {
+            let len = self.num_incoming.get(account).unwrap_or_default();
+            Self::page(len, offset, limit, |index| {
+                self.incoming.get((account, index))
+            })
+        }
+
+        #[ink(message)]
+        pub fn get_primary_domains(
+            &self,
+            router_addr: AccountId,
+            account: AccountId,
+            tld: Option<String>,
+        ) -> Vec<(AccountId, String)> {
+            let router: ink::contract_ref!(AznsContract) = router_addr.into();
+
+            router.get_primary_domains(account, tld)
+        }
+
+        /// Signed up accounts which still hold their own ticket.
+        fn is_attendee(&self, account: AccountId) -> bool {
+            self.ticket_of(account)
+                .and_then(|id| psp34::Internal::_owner_of(self, &id))
+                == Some(account)
+        }
+
+        /// Whether `a` and `b` share a confirmed handshake, in either direction.
+        fn is_connected(&self, a: AccountId, b: AccountId) -> bool {
+            self.connected.contains(Self::pair(a, b))
+        }
+
+        /// Orders two accounts so a handshake has the same key from both sides.
+        fn pair(a: AccountId, b: AccountId) -> (AccountId, AccountId) {
+            if a < b {
+                (a, b)
+            } else {
+                (b, a)
+            }
+        }
+
+        /// Removes the pending handshake `(from, to)` by moving the last incoming
+        /// entry of `to` and the last outgoing entry of `from` into its slots.
+        /// Returns whether there was one.
+        fn remove_pending(&mut self, from: AccountId, to: AccountId) -> bool {
+            let Some(index) = self.pending.take((from, to)) else {
+                return false;
+            };
+            let last = self.num_incoming.get(to).unwrap_or_default() - 1;
+            if index != last {
+                if let Some(moved) = self.incoming.get((to, last)) {
+                    self.incoming.insert((to, index), &moved);
+                    self.pending.insert((moved, to), &index);
+                }
+            }
+            self.incoming.remove((to, last));
+            self.num_incoming.insert(to, &last);
+
+            let index = self.outgoing_index.take((from, to)).unwrap_or_default();
+            let last = self.num_outgoing.get(from).unwrap_or_default() - 1;
+            if index != last {
+                if let Some(moved) = self.outgoing.get((from, last)) {
+                    self.outgoing.insert((from, index), &moved);
+                    self.outgoing_index.insert((from, moved), &index);
+                }
+            }
+            self.outgoing.remove((from, last));
+            self.num_outgoing.insert(from, &last);
+            true
+        }
+
+        /// Records the confirmed handshake between `from` and `to`.
+        fn connect(&mut self, from: AccountId, to: AccountId) {
+            self.connected.insert(Self::pair(from, to), &());
+            self.add_connection(from, to);
+            self.add_connection(to, from);
+            self.num_confirmed += 1;
+            self.env().emit_event(HandshakeConfirmed { from, to });
+        }
+
+        fn add_connection(&mut self, account: AccountId, other: AccountId) {
+            let index = self.num_connections.get(account).unwrap_or_default();
+            self.connections.insert((account, index), &other);
+            self.num_connections.insert(account, &(index + 1));
+            if self.split_rule == SplitRule::MinHandshakes(index + 1) {
+                self.num_qualified += 1;
+            }
+            self.promote(account, index);
+        }
+
+        /// Moves `account` up the ranking as its handshakes grow from `count` by one.
+        ///
+        /// Accounts with the same number of handshakes form a block, so swapping
+        /// `account` with the first of its block keeps the ranking sorted.
+        fn promote(&mut self, account: AccountId, count: u32) {
+            let position = if count == 0 {
+                // new accounts join at the end, below everyone with handshakes
+                let position = self.num_ranked;
+                self.num_ranked += 1;
+                position
+            } else {
+                let position = self.ranks.get(account).unwrap_or_default();
+                let start = self.rank_starts.get(count).unwrap_or_default();
+                if let Some(first) = self.ranking.get(start).filter(|_| start != position) {
+                    self.ranking.insert(position, &first);
+                    self.ranks.insert(first, &position);
+                }
+                self.rank_starts.insert(count, &(start + 1));
+                start
+            };
+            self.ranking.insert(position, &account);
+            self.ranks.insert(account, &position);
+            if !self.rank_starts.contains(count + 1) {
+                self.rank_starts.insert(count + 1, &position);
+            }
+        }
+
+        /// Collects up to `limit` (at most [`MAX_PAGE_SIZE`]) entries of a list of `len`.
+        fn page<T>(len: u32, offset: u32, limit: u32, get: impl Fn(u32) -> Option<T>) -> Vec<T> {
+            let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(len);
+            (offset..end).filter_map(get).collect()
+        }
+
+        /// Accounts `account` shares a confirmed handshake with, in the order they met,
+        /// `limit` entries starting at `offset`.
+        #[ink(message)]
+        pub fn connections_of(
+            &self,
+            account: AccountId,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<AccountId> {
+            Self::page(self.handshake_count(account), offset, limit, |index| {
+                self.connections.get((account, index))
+            })
+        }
+
+        /// Pending handshakes sent by `account`, `limit` entries starting at `offset`.
+        #[ink(message)]
+        pub fn outgoing_pending(
+            &self,
+            account: AccountId,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<AccountId> {
+            let len = self.num_outgoing.get(account).unwrap_or_default();
+            Self::page(len, offset, limit, |index| {
+                self.outgoing.get((account, index))
+            })
+        }
+
+        /// Whether `a` and `b` share a confirmed handshake.
+        #[ink(message)]
+        pub fn are_connected(&self, a: AccountId, b: AccountId) -> bool {
+            self.is_connected(a, b)
+        }
+
+        /// Accounts with confirmed handshakes and their number, ranked by the most
+        /// handshakes, `limit` entries starting at `offset`.
+        #[ink(message)]
+        pub fn leaderboard(&self, offset: u32, limit: u32) -> Vec<(AccountId, u32)> {
+            Self::page(self.num_ranked, offset, limit, |position| {
+                let account = self.ranking.get(position)?;
+                Some((account, self.handshake_count(account)))
+            })
+        }
+
+        /// Position of `account` on the leaderboard, starting at `0`.
+        #[ink(message)]
+        pub fn rank_of(&self, account: AccountId) -> Option<u32> {
+            self.ranks.get(account)
+        }
+
+        /// Number of confirmed handshakes `account` took part in.
+        #[ink(message)]
+        pub fn handshake_count(&self, account: AccountId) -> u32 {
+            self.num_connections.get(account).unwrap_or_default()
+        }
+
+        /// Cashback `account` is entitled to.
+        ///
+        /// The pool of all ticket payments is split by each attendee's share of
+        /// confirmed handshakes. If nobody networked, everyone gets their stake back.
+        /// The sponsor pool is added on top, split by the split rule.
+        #[ink(message)]
+        pub fn cashback_of(&self, account: AccountId) -> Balance {
+            if !self.is_registered(account) {
+                return 0;
+            }
+            // every confirmed handshake counts for both of its parties
+            let total = 2 * self.num_confirmed as Balance;
+            if total == 0 {
+                return self.after_fee(self.stake_of(account));
+            }
+            let count = self.handshake_count(account);
+            let sponsor_pool = self.after_fee(self.sponsor_pool);
+            let sponsor_share = match self.split_rule {
+                SplitRule::Proportional => sponsor_pool.saturating_mul(count as Balance) / total,
+                SplitRule::MinHandshakes(min) if count >= min => sponsor_pool
+                    .checked_div(self.num_qualified as Balance)
+                    .unwrap_or_default(),
+                SplitRule::MinHandshakes(_) => 0,
+            };
+            self.after_fee(self.pool).saturating_mul(count as Balance) / total + sponsor_share
+        }
+
+        /// Cashback `account` can still be paid out.
+        #[ink(message)]
+        pub fn claimable(&self, account: AccountId) -> Balance {
+            if self.has_claimed(account) {
+                return 0;
+            }
+            self.cashback_of(account)
+        }
+
+        /// Pays out the caller's cashback. Returns the paid value, which is `0`
+        /// once the caller has claimed, so repeated claims are no-ops.
+        #[ink(message)]
+        pub fn claim(&mut self) -> Result<Balance, HandshakeError> {
+            let caller = self.env().caller();
+            self.ensure_phase(Phase::Settlement)?;
+            if !self.is_registered(caller) {
+                return Err(HandshakeError::NotRegistered);
+            }
+            if self.has_claimed(caller) {
+                return Ok(0);
+            }
+            let value = self.cashback_of(caller);
+            if value > self.holdings() {
+                return Err(HandshakeError::InsufficientFunds);
+            }
+            // settle the accounting before any transfer, so a reentrant claim pays nothing
+            self.claimed.insert(caller, &());
+            self.num_claimed += 1;
+            self.total_paid_out += value;
+            self.settle_ticket(caller)?;
+            self.send(caller, value)?;
+            self.env().emit_event(PaidOut {
+                account: caller,
+                value,
+            });
+            Ok(value)
+        }
+
+        /// Records the handshake count and achievement tier of `account` on its
+        /// ticket. Anyone can settle any ticket during settlement, so attendees
+        /// get their tier without claiming; repeated calls are no-ops.
+        #[ink(message)]
+        pub fn settle_ticket(&mut self, account: AccountId) -> Result<(), HandshakeError> {
+            self.ensure_phase(Phase::Settlement)?;
+            let Some(id) = self.ticket_of(account) else {
+                return Err(HandshakeError::NotRegistered);
+            };
+            if self.settled.contains(account) {
+                return Ok(());
+            }
+            self.settled.insert(account, &());
+            let count = self.handshake_count(account);
+            metadata::Internal::_set_attribute(
+                self,
+                id.clone(),
+                String::from("handshakes"),
+                count.to_string(),
+            );
+            if let Some(tier) = self.tier_of(account) {
+                metadata::Internal::_set_attribute(
+                    self,
+                    id,
+                    String::from("tier"),
+                    String::from(tier.name()),
+                );
+                self.env().emit_event(TierAwarded { account, tier });
+            }
+            Ok(())
+        }
+
+        /// Whether the ticket of `account` recorded its networking result.
+        #[ink(message)]
+        pub fn is_settled(&self, account: AccountId) -> bool {
+            self.settled.contains(account)
+        }
+
+        /// Whether `account` already claimed its cashback.
+        #[ink(message)]
+        pub fn has_claimed(&self, account: AccountId) -> bool {
+            self.claimed.contains(account)
+        }
+
+        #[ink(message)]
+        pub fn ticket_price(&self) -> Balance {
+            self.ticket_price
+        }
+
+        /// Changes the price of future signups.
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn set_ticket_price(&mut self, ticket_price: Balance) -> Result<(), HandshakeError> {
+            self.ticket_price = ticket_price;
+            Ok(())
+        }
+
+        /// Current phase, the later of the phase set by the owner and the scheduled one.
+        #[ink(message)]
+        pub fn phase(&self) -> Phase {
+            let now = self.env().block_timestamp();
+            let reached = |start: Timestamp| start != 0 && now >= start;
+            let scheduled = if reached(self.closes_at) {
+                Phase::Closed
+            } else if reached(self.settlement_starts) {
+                Phase::Settlement
+            } else if reached(self.networking_starts) {
+                Phase::Networking
+            } else {
+                Phase::Registration
+            };
+            self.phase.max(scheduled)
+        }
+
+        /// Start timestamps of the networking and settlement phases and the closing time.
+        #[ink(message)]
+        pub fn schedule(&self) -> (Timestamp, Timestamp, Timestamp) {
+            (
+                self.networking_starts,
+                self.settlement_starts,
+                self.closes_at,
+            )
+        }
+
+        /// Lets the event pass through its phases at the given block timestamps,
+        /// possible during registration. Claims stay open for at least
+        /// [`MIN_CLAIM_WINDOW`] after settlement starts.
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn set_schedule(
+            &mut self,
+            networking_starts: Timestamp,
+            settlement_starts: Timestamp,
+            closes_at: Timestamp,
+        ) -> Result<(), HandshakeError> {
+            self.ensure_phase(Phase::Registration)?;
+            if networking_starts <= self.env().block_timestamp()
+                || networking_starts >= settlement_starts
+                || closes_at < settlement_starts.saturating_add(MIN_CLAIM_WINDOW)
+            {
+                return Err(HandshakeError::InvalidSchedule);
+            }
+            self.networking_starts = networking_starts;
+            self.settlement_starts = settlement_starts;
+            self.closes_at = closes_at;
+            Ok(())
+        }
+
+        /// Moves the event on to its next phase ahead of the schedule.
+        ///
+        /// Starting settlement fixes the claim deadline, at least [`MIN_CLAIM_WINDOW`]
+        /// ahead. Settlement only ends early once every attendee claimed.
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn advance_phase(&mut self) -> Result<Phase, HandshakeError> {
+            self.phase = match self.phase() {
+                Phase::Registration => Phase::Networking,
+                Phase::Networking => {
+                    let deadline = self
+                        .env()
+                        .block_timestamp()
+                        .saturating_add(MIN_CLAIM_WINDOW);
+                    self.closes_at = self.closes_at.max(deadline);
+                    Phase::Settlement
+                }
+                Phase::Settlement => {
+                    self.ensure_all_claimed()?;
+                    Phase::Closed
+                }
+                Phase::Closed => return Err(HandshakeError::EventClosed),
+            };
+            Ok(self.phase)
+        }
+
+        /// Ends the event right away, possible during settlement once every
+        /// attendee claimed.
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn close_event(&mut self) -> Result<(), HandshakeError> {
+            self.ensure_phase(Phase::Settlement)?;
+            self.ensure_all_claimed()?;
+            self.phase = Phase::Closed;
+            Ok(())
+        }
+
+        fn ensure_all_claimed(&self) -> Result<(), HandshakeError> {
+            if self.num_claimed < self.num_accounts {
+                return Err(HandshakeError::ClaimWindowOpen);
+            }
+            Ok(())
+        }
+
+        fn ensure_phase(&self, phase: Phase) -> Result<(), HandshakeError> {
+            match self.phase() {
+                current if current == phase => Ok(()),
+                Phase::Closed => Err(HandshakeError::EventClosed),
+                _ => Err(HandshakeError::WrongPhase),
+            }
+        }
+
+        /// Handshakes needed for bronze, silver and gold.
+        #[ink(message)]
+        pub fn tier_thresholds(&self) -> (u32, u32, u32) {
+            self.tier_thresholds
+        }
+
+        /// Configures the achievement tiers, possible until settlement starts.
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn set_tier_thresholds(
+            &mut self,
+            bronze: u32,
+            silver: u32,
+            gold: u32,
+        ) -> Result<(), HandshakeError> {
+            if self.phase() >= Phase::Settlement {
+                return Err(HandshakeError::WrongPhase);
+            }
+            if bronze == 0 || bronze >= silver || silver >= gold {
+                return Err(HandshakeError::InvalidThresholds);
+            }
+            self.tier_thresholds = (bronze, silver, gold);
+            Ok(())
+        }
+
+        /// Achievement tier `account` reached with its confirmed handshakes.
+        #[ink(message)]
+        pub fn tier_of(&self, account: AccountId) -> Option<Tier> {
+            let (bronze, silver, gold) = self.tier_thresholds;
+            if bronze == 0 || !self.is_registered(account) {
+                return None;
+            }
+            match self.handshake_count(account) {
+                count if count >= gold => Some(Tier::Gold),
+                count if count >= silver => Some(Tier::Silver),
+                count if count >= bronze => Some(Tier::Bronze),
+                _ => None,
+            }
+        }
+
+        /// Balance exceeding what is still owed to the attendees, including the
+        /// organizer fee.
+        #[ink(message)]
+        pub fn organizer_share(&self) -> Balance {
+            let owed = (self.after_fee(self.pool) + self.after_fee(self.sponsor_pool_owed()))
+                .saturating_sub(self.total_paid_out);
+            self.holdings().saturating_sub(owed)
+        }
+
+        /// Transfers the organizer share to the owner, possible once settlement
+        /// starts and the fee can no longer change.
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn withdraw_organizer_share(&mut self) -> Result<Balance, HandshakeError> {
+            if self.phase() < Phase::Settlement {
+                return Err(HandshakeError::WrongPhase);
+            }
+            let owner = self.env().caller();
+            let value = self.organizer_share();
+            self.send(owner, value)?;
+            self.env()
+                .emit_event(OrganizerShareWithdrawn { owner, value });
+            Ok(value)
+        }
+
+        /// Transfers everything left to the owner once the claim deadline passed.
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn sweep_unclaimed(&mut self) -> Result<Balance, HandshakeError> {
+            // the deadline is set once settlement starts and can not be moved by the owner
+            if self.closes_at == 0 || self.env().block_timestamp() < self.closes_at {
+                return Err(HandshakeError::ClaimWindowOpen);
+            }
+            let owner = self.env().caller();
+            let value = self.holdings();
+            self.send(owner, value)?;
+            self.env().emit_event(UnclaimedSwept { owner, value });
+            Ok(value)
+        }
+
+        /// Share of both pools kept by the organizer, in basis points.
+        #[ink(message)]
+        pub fn organizer_fee(&self) -> u16 {
+            self.organizer_fee
+        }
+
+        /// Configures the organizer fee, possible during registration.
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn set_organizer_fee(&mut self, organizer_fee: u16) -> Result<(), HandshakeError> {
+            self.ensure_phase(Phase::Registration)?;
+            if organizer_fee > BASIS_POINTS {
+                return Err(HandshakeError::InvalidFee);
+            }
+            self.organizer_fee = organizer_fee;
+            Ok(())
+        }
+
+        /// Part of `value` left to the attendees after the organizer fee.
+        fn after_fee(&self, value: Balance) -> Balance {
+            value.saturating_mul((BASIS_POINTS - self.organizer_fee) as Balance)
+                / BASIS_POINTS as Balance
+        }
+
+        /// Adds `value` to the sponsor pool, possible until settlement starts.
+        #[ink(message, payable)]
+        pub fn fund_pool(&mut self, value: Balance) -> Result<(), HandshakeError> {
+            match self.phase() {
+                Phase::Registration | Phase::Networking => {}
+                Phase::Settlement => return Err(HandshakeError::WrongPhase),
+                Phase::Closed => return Err(HandshakeError::EventClosed),
+            }
+            let sponsor = self.env().caller();
+            self.collect(sponsor, value)?;
+            self.sponsor_pool += value;
+            self.env().emit_event(PoolFunded { sponsor, value });
+            Ok(())
+        }
+
+        /// Sum of the stakes of all attendees.
+        #[ink(message)]
+        pub fn total_staked(&self) -> Balance {
+            self.pool
+        }
+
+        #[ink(message)]
+        pub fn sponsor_pool(&self) -> Balance {
+            self.sponsor_pool
+        }
+
+        #[ink(message)]
+        pub fn split_rule(&self) -> SplitRule {
+            self.split_rule
+        }
+
+        /// Configures how the sponsor pool is split, possible during registration.
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn set_split_rule(&mut self, rule: SplitRule) -> Result<(), HandshakeError> {
+            self.ensure_phase(Phase::Registration)?;
+            if rule == SplitRule::MinHandshakes(0) {
+                return Err(HandshakeError::InvalidSplitRule);
+            }
+            self.split_rule = rule;
+            Ok(())
+        }
+
+        /// Part of the sponsor pool still owed to the attendees. Once settlement
+        /// starts without any attendee qualifying, it is left to the organizer.
+        fn sponsor_pool_owed(&self) -> Balance {
+            let qualified = match self.split_rule {
+                SplitRule::Proportional => self.num_confirmed > 0,
+                SplitRule::MinHandshakes(_) => self.num_qualified > 0,
+            };
+            if !qualified && self.phase() >= Phase::Settlement {
+                return 0;
+            }
+            self.sponsor_pool
+        }
+
+        /// PSP22 token stakes and cashbacks are paid in, `None` for native balance.
+        #[ink(message)]
+        pub fn token(&self) -> Option<AccountId> {
+            self.token
+        }
+
+        /// Takes `value` in the currency of the stakes from `from`, the caller.
+        /// Native value transferred on top is refunded.
+        fn collect(&mut self, from: AccountId, value: Balance) -> Result<(), HandshakeError> {
+            let mut surplus = Self::env().transferred_value();
+            match self.token {
+                Some(token) => {
+                    let mut token: ink::contract_ref!(PSP22) = token.into();
+                    token.transfer_from(from, Self::env().account_id(), value, Vec::new())?;
+                }
+                None if surplus < value => return Err(HandshakeError::InsufficientPayment),
+                None => surplus -= value,
+            }
+            if surplus > 0 && self.env().transfer(from, surplus).is_err() {
+                return Err(HandshakeError::TransferFailed);
+            }
+            Ok(())
+        }
+
+        /// Balance of the contract in the currency of the stakes.
+        fn holdings(&self) -> Balance {
+            match self.token {
+                Some(token) => {
+                    let token: ink::contract_ref!(PSP22) = token.into();
+                    token.balance_of(self.env().account_id())
+                }
+                None => self.env().balance(),
+            }
+        }
+
+        /// Transfers `value` in the currency of the stakes to `to`.
+        fn send(&mut self, to: AccountId, value: Balance) -> Result<(), HandshakeError> {
+            if value == 0 {
+                return Ok(());
+            }
+            match self.token {
+                Some(token) => {
+                    let mut token: ink::contract_ref!(PSP22) = token.into();
+                    token.transfer(to, value, Vec::new())?;
+                }
+                None => {
+                    if self.env().transfer(to, value).is_err() {
+                        return Err(HandshakeError::TransferFailed);
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        type Event = <Handshake as ::ink::reflect::ContractEventBase>::Type;
+
+        fn recorded_events() -> Vec<Event> {
+            ink::env::test::recorded_events()
+                .map(|event| <Event as scale::Decode>::decode(&mut &event.data[..]).unwrap())
+                .collect()
+        }
+
+        fn new_event(ticket_price: Balance) -> Handshake {
+            Handshake::new(ticket_price, String::from("Handshake"), String::from("HSK"))
+        }
+
+        fn signup(contract: &mut Handshake, account: AccountId) {
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(account);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                contract.ticket_price,
+            );
+            assert_eq!(contract.signup(), Ok(()));
+        }
+
+        fn advance_to(contract: &mut Handshake, phase: Phase) {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            while contract.phase() < phase {
+                if contract.phase() == Phase::Settlement {
+                    // settlement ends at the claim deadline
+                    let (_, _, closes_at) = contract.schedule();
+                    ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(closes_at);
+                } else {
+                    assert!(contract.advance_phase().is_ok());
+                }
+            }
+        }
+
+        #[ink::test]
+        fn new_contract_works() {
+            let contract = new_event(7);
+            assert_eq!(contract.num_accounts(), Ok(0));
+            assert_eq!(contract.num_handshakes(), Ok(0));
+            assert_eq!(contract.token(), None);
+        }
+
+        #[ink::test]
+        fn token_is_set_at_construction() {
+            let token = AccountId::from([0x22; 32]);
+            let contract =
+                Handshake::with_token(7, String::from("Handshake"), String::from("HSK"), token);
+            assert_eq!(contract.token(), Some(token));
+            assert_eq!(contract.ticket_price(), 7);
+        }
+
+        #[ink::test]
+        fn handshake_counts_after_confirmation() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = new_event(7);
+            signup(&mut contract, accounts.alice);
+            signup(&mut contract, accounts.bob);
+            advance_to(&mut contract, Phase::Networking);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.handshake(accounts.bob), Ok(()));
+            assert_eq!(contract.num_handshakes(), Ok(0));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.open_handshakes(), Ok(vec![accounts.alice]));
+            assert_eq!(contract.confirm_handshake(accounts.alice), Ok(()));
+            assert_eq!(contract.num_handshakes(), Ok(1));
+            assert_eq!(contract.open_handshakes(), Ok(vec![]));
+        }
+
+        #[ink::test]
+        fn confirm_without_request_fails() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = new_event(7);
+            advance_to(&mut contract, Phase::Networking);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                contract.confirm_handshake(accounts.alice),
+                Err(HandshakeError::NoPendingHandshake)
+            );
+            assert_eq!(contract.num_handshakes(), Ok(0));
+        }
+
+        #[ink::test]
+        fn pending_handshakes_can_be_withdrawn() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = new_event(7);
+            for account in [accounts.alice, accounts.bob, accounts.charlie] {
+                signup(&mut contract, account);
+            }
+            advance_to(&mut contract, Phase::Networking);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.handshake(accounts.bob), Ok(()));
+            assert_eq!(contract.handshake(accounts.charlie), Ok(()));
+            assert_eq!(contract.cancel_handshake(accounts.bob), Ok(()));
+            assert_eq!(
+                contract.cancel_handshake(accounts.bob),
+                Err(HandshakeError::NoPendingHandshake)
+            );
+            assert_eq!(
+                contract.outgoing_pending(accounts.alice, 0, 10),
+                vec![accounts.charlie]
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.open_handshakes(), Ok(vec![]));
+            assert_eq!(
+                contract.confirm_handshake(accounts.alice),
+                Err(HandshakeError::NoPendingHandshake)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.reject_handshake(accounts.alice), Ok(()));
+            assert_eq!(contract.open_handshakes(), Ok(vec![]));
+            assert_eq!(contract.outgoing_pending(accounts.alice, 0, 10), vec![]);
+
+            // a withdrawn request can be sent again
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.handshake(accounts.charlie), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.confirm_handshake(accounts.alice), Ok(()));
+            assert_eq!(contract.num_handshakes(), Ok(1));
+            assert_eq!(
+                contract.reject_handshake(accounts.alice),
+                Err(HandshakeError::NoPendingHandshake)
+            );
+
+            advance_to(&mut contract, Phase::Settlement);
+            assert_eq!(
+                contract.cancel_handshake(accounts.bob),
+                Err(HandshakeError::WrongPhase)
+            );
+            let events = recorded_events();
+            assert!(events.iter().any(|event| matches!(
+                event,
+                Event::HandshakeCancelled(HandshakeCancelled { from, to })
+                    if *from == accounts.alice && *to == accounts.bob
+            )));
+            assert!(events.iter().any(|event| matches!(
+                event,
+                Event::HandshakeRejected(HandshakeRejected { from, to })
+                    if *from == accounts.alice && *to == accounts.charlie
+            )));
+        }
+
+        #[ink::test]
+        fn handshakes_carry_metadata() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = new_event(7);
+            for account in [accounts.alice, accounts.bob, accounts.charlie] {
+                signup(&mut contract, account);
+            }
+            advance_to(&mut contract, Phase::Networking);
+
+            let hiring = HandshakeMetadata {
+                note: String::from("hiring"),
+                contact: Some(vec![7; MAX_CONTACT_LENGTH as usize]),
+            };
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                contract.handshake_with_metadata(accounts.bob, hiring.clone()),
+                Ok(())
+            );
+            assert_eq!(
+                contract.handshake_metadata(accounts.alice, accounts.bob),
+                Some(hiring.clone())
+            );
+            let too_long = HandshakeMetadata {
+                note: "a".repeat(MAX_NOTE_LENGTH as usize + 1),
+                contact: None,
+            };
+            assert_eq!(
+                contract.handshake_with_metadata(accounts.charlie, too_long),
+                Err(HandshakeError::MetadataTooLarge)
+            );
+            let too_large = HandshakeMetadata {
+                contact: Some(vec![7; MAX_CONTACT_LENGTH as usize + 1]),
+                ..hiring.clone()
+            };
+            assert_eq!(
+                contract.handshake_with_metadata(accounts.charlie, too_large),
+                Err(HandshakeError::MetadataTooLarge)
+            );
+            assert_eq!(
+                contract.outgoing_pending(accounts.alice, 0, 10),
+                vec![accounts.bob]
+            );
+
+            // the receiver attaches its own metadata when confirming
+            let follow_up = HandshakeMetadata {
+                note: String::from("talk follow-up"),
+                contact: None,
+            };
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                contract.handshake_with_metadata(accounts.alice, follow_up.clone()),
+                Ok(())
+            );
+            assert!(contract.are_connected(accounts.alice, accounts.bob));
+            assert_eq!(
+                contract.handshake_metadata(accounts.bob, accounts.alice),
+                Some(follow_up)
+            );
+
+            // withdrawn requests drop their metadata
+            assert_eq!(
+                contract.handshake_with_metadata(accounts.charlie, hiring),
+                Ok(())
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.reject_handshake(accounts.bob), Ok(()));
+            assert_eq!(
+                contract.handshake_metadata(accounts.bob, accounts.charlie),
+                None
+            );
+        }
+
+        #[ink::test]
+        fn attendees_publish_contact_keys() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = new_event(7);
+            signup(&mut contract, accounts.alice);
+
+            assert_eq!(contract.contact_key(accounts.alice), None);
+            assert_eq!(contract.set_contact_key([1; 32]), Ok(()));
+            assert_eq!(contract.contact_key(accounts.alice), Some([1; 32]));
+            assert_eq!(contract.set_contact_key([2; 32]), Ok(()));
+            assert_eq!(contract.contact_key(accounts.alice), Some([2; 32]));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                contract.set_contact_key([3; 32]),
+                Err(HandshakeError::NotRegistered)
+            );
+            assert_eq!(contract.contact_key(accounts.bob), None);
+        }
+
+        #[ink::test]
+        fn cashback_follows_handshakes() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = new_event(10);
+
+            for account in [accounts.alice, accounts.bob, accounts.charlie] {
+                signup(&mut contract, account);
+            }
+            assert_eq!(contract.cashback_of(accounts.alice), 10);
+            advance_to(&mut contract, Phase::Networking);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.handshake(accounts.bob), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.confirm_handshake(accounts.alice), Ok(()));
+
+            assert_eq!(contract.cashback_of(accounts.alice), 15);
+            assert_eq!(contract.cashback_of(accounts.bob), 15);
+            assert_eq!(contract.cashback_of(accounts.charlie), 0);
+            assert_eq!(contract.cashback_of(accounts.django), 0);
+        }
+
+        #[ink::test]
+        fn claims_wait_for_final_handshakes() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = new_event(10);
+            for account in [accounts.alice, accounts.bob, accounts.charlie] {
+                signup(&mut contract, account);
+            }
+            advance_to(&mut contract, Phase::Networking);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.handshake(accounts.bob), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.confirm_handshake(accounts.alice), Ok(()));
+
+            // a claim on the split so far would leave too little for later networkers
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.cashback_of(accounts.alice), 15);
+            assert_eq!(contract.claim(), Err(HandshakeError::WrongPhase));
+            assert!(!contract.has_claimed(accounts.alice));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.handshake(accounts.charlie), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.confirm_handshake(accounts.bob), Ok(()));
+            advance_to(&mut contract, Phase::Settlement);
+
+            let mut paid = 0;
+            for (account, cashback) in [
+                (accounts.alice, 7),
+                (accounts.bob, 15),
+                (accounts.charlie, 7),
+            ] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(account);
+                assert_eq!(contract.claim(), Ok(cashback));
+                paid += cashback;
+            }
+            assert!(paid <= contract.total_staked());
+        }
+
+        #[ink::test]
+        fn claim_is_idempotent() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = new_event(10);
+            signup(&mut contract, accounts.alice);
+            advance_to(&mut contract, Phase::Settlement);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert!(!contract.has_claimed(accounts.alice));
+            assert_eq!(contract.claim(), Ok(10));
+            assert!(contract.has_claimed(accounts.alice));
+            assert_eq!(contract.claim(), Ok(0));
+            assert_eq!(contract.claim(), Ok(0));
+            assert_eq!(contract.claimable(accounts.alice), 0);
+            assert_eq!(
+                recorded_events()
+                    .iter()
+                    .filter(|event| matches!(event, Event::PaidOut(_)))
+                    .count(),
+                1
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.claim(), Err(HandshakeError::NotRegistered));
+        }
+
+        #[ink::test]
+        fn signup_requires_ticket_price() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = new_event(10);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(9);
+            assert_eq!(contract.signup(), Err(HandshakeError::InsufficientPayment));
+            assert_eq!(contract.num_accounts(), Ok(0));
+        }
+
+        #[ink::test]
+        fn signup_only_once() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = new_event(7);
+            assert!(!contract.is_registered(accounts.alice));
+            assert_eq!(contract.ticket_of(accounts.alice), None);
+
+            signup(&mut contract, accounts.alice);
+            signup(&mut contract, accounts.bob);
+            assert!(contract.is_registered(accounts.alice));
+            assert_eq!(contract.ticket_of(accounts.alice), Some(Id::U32(0)));
+            assert_eq!(contract.ticket_of(accounts.bob), Some(Id::U32(1)));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.signup(), Err(HandshakeError::AlreadyRegistered));
+            assert_eq!(contract.num_accounts(), Ok(2));
+        }
+
+        #[ink::test]
+        fn mints_more_than_256_tickets() {
+            let mut contract = new_event(0);
+            for i in 0..300u32 {
+                let mut account = [0xAA; 32];
+                account[..4].copy_from_slice(&i.to_le_bytes());
+                signup(&mut contract, AccountId::from(account));
+            }
+            assert_eq!(contract.num_accounts(), Ok(300));
+
+            let mut last = [0xAA; 32];
+            last[..4].copy_from_slice(&299u32.to_le_bytes());
+            assert_eq!(
+                contract.ticket_of(AccountId::from(last)),
+                Some(Id::U32(299))
+            );
+        }
+
+        #[ink::test]
+        fn tickets_carry_metadata() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = new_event(7);
+            let collection_id = Id::Bytes(
+                <AccountId as AsRef<[u8; 32]>>::as_ref(&ink::env::account_id::<
+                    ink::env::DefaultEnvironment,
+                >())
+                .to_vec(),
+            );
+            assert_eq!(
+                PSP34Metadata::get_attribute(&contract, collection_id, String::from("symbol")),
+                Some(String::from("HSK"))
+            );
+
+            signup(&mut contract, accounts.alice);
+            let ticket = contract.ticket_of(accounts.alice).unwrap();
+            let attribute = |contract: &Handshake, key: &str| {
+                PSP34Metadata::get_attribute(contract, ticket.clone(), String::from(key))
+            };
+            assert_eq!(
+                attribute(&contract, "event"),
+                Some(String::from("Handshake"))
+            );
+            assert_eq!(attribute(&contract, "tier"), Some(String::from("attendee")));
+            assert_eq!(attribute(&contract, "handshakes"), None);
+            assert_eq!(
+                contract.settle_ticket(accounts.alice),
+                Err(HandshakeError::WrongPhase)
+            );
+
+            // the handshake count is recorded without alice claiming
+            advance_to(&mut contract, Phase::Settlement);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.settle_ticket(accounts.alice), Ok(()));
+            assert_eq!(attribute(&contract, "handshakes"), Some(String::from("0")));
+            assert!(!contract.has_claimed(accounts.alice));
+        }
+
+        #[ink::test]
+        fn settlement_upgrades_tickets_by_tier() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = new_event(10);
+            assert_eq!(
+                contract.set_tier_thresholds(2, 2, 3),
+                Err(HandshakeError::InvalidThresholds)
+            );
+            assert_eq!(contract.set_tier_thresholds(1, 2, 3), Ok(()));
+            assert_eq!(contract.tier_thresholds(), (1, 2, 3));
+
+            let attendees = [
+                accounts.alice,
+                accounts.bob,
+                accounts.charlie,
+                accounts.django,
+            ];
+            for account in attendees {
+                signup(&mut contract, account);
+            }
+            advance_to(&mut contract, Phase::Networking);
+            // alice meets everybody, bob meets alice and charlie
+            for (from, to) in [
+                (accounts.bob, accounts.alice),
+                (accounts.charlie, accounts.alice),
+                (accounts.django, accounts.alice),
+                (accounts.charlie, accounts.bob),
+            ] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(from);
+                assert_eq!(contract.handshake(to), Ok(()));
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(to);
+                assert_eq!(contract.confirm_handshake(from), Ok(()));
+            }
+            assert_eq!(contract.tier_of(accounts.alice), Some(Tier::Gold));
+            assert_eq!(contract.tier_of(accounts.bob), Some(Tier::Silver));
+            assert_eq!(contract.tier_of(accounts.django), Some(Tier::Bronze));
+            assert_eq!(contract.tier_of(accounts.eve), None);
+
+            advance_to(&mut contract, Phase::Settlement);
+            assert_eq!(
+                contract.set_tier_thresholds(1, 2, 3),
+                Err(HandshakeError::WrongPhase)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert!(contract.claim().is_ok());
+            let attribute = |contract: &Handshake, account: AccountId, key: &str| {
+                let ticket = contract.ticket_of(account).unwrap();
+                PSP34Metadata::get_attribute(contract, ticket, String::from(key))
+            };
+            assert_eq!(
+                attribute(&contract, accounts.alice, "tier"),
+                Some(String::from("gold"))
+            );
+
+            // bob never claims, anyone can settle his ticket for him
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(
+                contract.settle_ticket(accounts.eve),
+                Err(HandshakeError::NotRegistered)
+            );
+            assert!(!contract.is_settled(accounts.bob));
+            assert_eq!(contract.settle_ticket(accounts.bob), Ok(()));
+            assert_eq!(contract.settle_ticket(accounts.bob), Ok(()));
+            assert!(contract.is_settled(accounts.bob));
+            assert!(!contract.has_claimed(accounts.bob));
+            assert_eq!(
+                attribute(&contract, accounts.bob, "tier"),
+                Some(String::from("silver"))
+            );
+            assert_eq!(
+                attribute(&contract, accounts.bob, "handshakes"),
+                Some(String::from("2"))
+            );
+
+            // claiming after settling does not award the tier twice
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert!(contract.claim().is_ok());
+            let awarded: Vec<_> = recorded_events()
+                .into_iter()
+                .filter_map(|event| match event {
+                    Event::TierAwarded(TierAwarded { account, tier }) => Some((account, tier)),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(
+                awarded,
+                vec![(accounts.alice, Tier::Gold), (accounts.bob, Tier::Silver)]
+            );
+        }
+
+        #[ink::test]
+        fn signup_refunds_surplus() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let contract_id = AccountId::from([0x42; 32]);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(contract_id);
+            let mut contract = new_event(10);
+            // the off-chain engine does not move the transferred value itself
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(contract_id, 15);
+            let balance_before =
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob)
+                    .unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(15);
+            assert_eq!(contract.signup(), Ok(()));
+
+            assert_eq!(contract.stake_of(accounts.bob), 10);
+            assert_eq!(
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob),
+                Ok(balance_before + 5)
+            );
+            assert_eq!(
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(contract_id),
+                Ok(10)
+            );
+        }
+
+        #[ink::test]
+        fn handshake_requires_registration() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = new_event(7);
+            signup(&mut contract, accounts.alice);
+            advance_to(&mut contract, Phase::Networking);
+
+            let not_registered = Err(HandshakeError::NotRegistered);
+            assert_eq!(contract.handshake(accounts.bob), not_registered);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.handshake(accounts.alice), not_registered);
+        }
+
+        #[ink::test]
+        fn handshake_requires_own_ticket() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = new_event(7);
+            for account in [accounts.alice, accounts.bob, accounts.charlie] {
+                signup(&mut contract, account);
+            }
+            advance_to(&mut contract, Phase::Networking);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.handshake(accounts.bob), Ok(()));
+
+            // alice and bob swap their tickets, so both hold a ticket but not their own
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let ticket_of_alice = contract.ticket_of(accounts.alice).unwrap();
+            let ticket_of_bob = contract.ticket_of(accounts.bob).unwrap();
+            assert!(
+                PSP34::transfer(&mut contract, accounts.bob, ticket_of_alice, Vec::new()).is_ok()
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert!(
+                PSP34::transfer(&mut contract, accounts.alice, ticket_of_bob, Vec::new()).is_ok()
+            );
+
+            let not_registered = Err(HandshakeError::NotRegistered);
+            assert_eq!(contract.handshake(accounts.charlie), not_registered);
+            assert_eq!(contract.confirm_handshake(accounts.charlie), not_registered);
+            assert_eq!(contract.handshake_count(accounts.bob), 0);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.handshake(accounts.alice), not_registered);
+        }
+
+        #[ink::test]
+        fn handshake_rejects_self_and_duplicates() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = new_event(7);
+            signup(&mut contract, accounts.alice);
+            signup(&mut contract, accounts.bob);
+            advance_to(&mut contract, Phase::Networking);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                contract.handshake(accounts.alice),
+                Err(HandshakeError::SelfHandshake)
+            );
+            assert_eq!(contract.handshake(accounts.bob), Ok(()));
+            assert_eq!(
+                contract.handshake(accounts.bob),
+                Err(HandshakeError::AlreadyRequested)
+            );
+
+            // a request in the opposite direction confirms the pending one
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.handshake(accounts.alice), Ok(()));
+            assert_eq!(contract.num_handshakes(), Ok(1));
+            assert_eq!(contract.open_handshakes(), Ok(vec![]));
+            assert_eq!(
+                contract.handshake(accounts.alice),
+                Err(HandshakeError::AlreadyConnected)
+            );
+            assert_eq!(contract.handshake_count(accounts.bob), 1);
+        }
+
+        #[ink::test]
+        fn only_owner_manages_event() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = new_event(7);
+            assert_eq!(Ownable::owner(&contract), Some(accounts.alice));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.set_ticket_price(1), Err(HandshakeError::NotOwner));
+            assert_eq!(contract.close_event(), Err(HandshakeError::NotOwner));
+            assert_eq!(
+                contract.withdraw_organizer_share(),
+                Err(HandshakeError::NotOwner)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.set_ticket_price(10), Ok(()));
+            assert_eq!(contract.ticket_price(), 10);
+            advance_to(&mut contract, Phase::Settlement);
+            assert_eq!(contract.close_event(), Ok(()));
+            assert_eq!(contract.phase(), Phase::Closed);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(10);
+            assert_eq!(contract.signup(), Err(HandshakeError::EventClosed));
+        }
+
+        #[ink::test]
+        fn messages_are_gated_by_phase() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = new_event(7);
+            signup(&mut contract, accounts.alice);
+            signup(&mut contract, accounts.bob);
+            assert_eq!(
+                contract.handshake(accounts.alice),
+                Err(HandshakeError::WrongPhase)
+            );
+            assert_eq!(contract.claim(), Err(HandshakeError::WrongPhase));
+
+            advance_to(&mut contract, Phase::Networking);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.handshake(accounts.alice), Ok(()));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(7);
+            assert_eq!(contract.signup(), Err(HandshakeError::WrongPhase));
+
+            advance_to(&mut contract, Phase::Settlement);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                contract.confirm_handshake(accounts.bob),
+                Err(HandshakeError::WrongPhase)
+            );
+
+            advance_to(&mut contract, Phase::Closed);
+            assert_eq!(contract.claim(), Err(HandshakeError::EventClosed));
+            assert_eq!(contract.advance_phase(), Err(HandshakeError::EventClosed));
+        }
+
+        #[ink::test]
+        fn phases_follow_schedule() {
+            let mut contract = new_event(7);
+            assert_eq!(
+                contract.set_schedule(20, 10, 30),
+                Err(HandshakeError::InvalidSchedule)
+            );
+            let closes_at = 20 + MIN_CLAIM_WINDOW;
+            assert_eq!(contract.set_schedule(10, 20, closes_at), Ok(()));
+            assert_eq!(contract.schedule(), (10, 20, closes_at));
+
+            let phase_at = |timestamp| {
+                ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(timestamp);
+                contract.phase()
+            };
+            assert_eq!(phase_at(5), Phase::Registration);
+            assert_eq!(phase_at(10), Phase::Networking);
+            assert_eq!(phase_at(25), Phase::Settlement);
+            assert_eq!(phase_at(closes_at), Phase::Closed);
+        }
+
+        #[ink::test]
+        fn lifecycle_can_not_be_cut_short() {
+            let mut contract = new_event(7);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(10);
+            assert_eq!(
+                contract.set_schedule(10, 20, 30),
+                Err(HandshakeError::InvalidSchedule)
+            );
+            assert_eq!(contract.close_event(), Err(HandshakeError::WrongPhase));
+
+            advance_to(&mut contract, Phase::Networking);
+            assert_eq!(
+                contract.set_schedule(20, 30, 40),
+                Err(HandshakeError::WrongPhase)
+            );
+            assert_eq!(contract.close_event(), Err(HandshakeError::WrongPhase));
+            assert_eq!(contract.phase(), Phase::Networking);
+
+            advance_to(&mut contract, Phase::Settlement);
+            assert_eq!(contract.close_event(), Ok(()));
+        }
+
+        #[ink::test]
+        fn withdraw_organizer_share_keeps_attendee_pool() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let contract_id = AccountId::from([0x42; 32]);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(contract_id);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = new_event(10);
+            signup(&mut contract, accounts.bob);
+            advance_to(&mut contract, Phase::Settlement);
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(contract_id, 25);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.organizer_share(), 15);
+            assert_eq!(contract.withdraw_organizer_share(), Ok(15));
+            assert_eq!(contract.organizer_share(), 0);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.claim(), Ok(10));
+        }
+
+        #[ink::test]
+        fn organizer_share_waits_for_settlement() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let contract_id = AccountId::from([0x42; 32]);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(contract_id);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = new_event(10);
+            assert_eq!(contract.set_organizer_fee(BASIS_POINTS / 2), Ok(()));
+            signup(&mut contract, accounts.bob);
+            signup(&mut contract, accounts.charlie);
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(contract_id, 20);
+
+            // taking the fee first and lowering it afterwards would underpay the attendees
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                contract.withdraw_organizer_share(),
+                Err(HandshakeError::WrongPhase)
+            );
+            assert_eq!(contract.set_organizer_fee(0), Ok(()));
+
+            advance_to(&mut contract, Phase::Settlement);
+            assert_eq!(contract.withdraw_organizer_share(), Ok(0));
+            for account in [accounts.bob, accounts.charlie] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(account);
+                assert_eq!(contract.claim(), Ok(10));
+            }
+        }
+
+        fn fund_pool(contract: &mut Handshake, sponsor: AccountId, value: Balance) {
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(sponsor);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(value);
+            assert_eq!(contract.fund_pool(value), Ok(()));
+        }
+
+        /// Bob shakes hands with Charlie and Django.
+        fn network(contract: &mut Handshake) {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            advance_to(contract, Phase::Networking);
+            for other in [accounts.charlie, accounts.django] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+                assert_eq!(contract.handshake(other), Ok(()));
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(other);
+                assert_eq!(contract.confirm_handshake(accounts.bob), Ok(()));
+            }
+        }
+
+        #[ink::test]
+        fn sponsor_pool_rewards_top_networkers() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let contract_id = AccountId::from([0x42; 32]);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(contract_id);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = new_event(10);
+            for account in [
+                accounts.alice,
+                accounts.bob,
+                accounts.charlie,
+                accounts.django,
+            ] {
+                signup(&mut contract, account);
+            }
+            fund_pool(&mut contract, accounts.eve, 20);
+            assert_eq!(contract.total_staked(), 40);
+            assert_eq!(contract.sponsor_pool(), 20);
+            network(&mut contract);
+
+            assert_eq!(contract.claimable(accounts.alice), 0);
+            assert_eq!(contract.claimable(accounts.bob), 30);
+            assert_eq!(contract.claimable(accounts.charlie), 15);
+            assert_eq!(contract.claimable(accounts.eve), 0);
+
+            advance_to(&mut contract, Phase::Settlement);
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(contract_id, 60);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.claim(), Ok(30));
+            assert_eq!(contract.claimable(accounts.bob), 0);
+            assert_eq!(contract.cashback_of(accounts.bob), 30);
+        }
+
+        #[ink::test]
+        fn sponsor_pool_split_by_min_handshakes() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = new_event(10);
+            for account in [accounts.bob, accounts.charlie, accounts.django] {
+                signup(&mut contract, account);
+            }
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                contract.set_split_rule(SplitRule::MinHandshakes(2)),
+                Err(HandshakeError::NotOwner)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                contract.set_split_rule(SplitRule::MinHandshakes(0)),
+                Err(HandshakeError::InvalidSplitRule)
+            );
+            assert_eq!(contract.set_split_rule(SplitRule::MinHandshakes(2)), Ok(()));
+            assert_eq!(contract.split_rule(), SplitRule::MinHandshakes(2));
+
+            fund_pool(&mut contract, accounts.eve, 20);
+            network(&mut contract);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                contract.set_split_rule(SplitRule::Proportional),
+                Err(HandshakeError::WrongPhase)
+            );
+
+            assert_eq!(contract.claimable(accounts.bob), 35);
+            assert_eq!(contract.claimable(accounts.charlie), 7);
+            assert_eq!(contract.claimable(accounts.django), 7);
+        }
+
+        #[ink::test]
+        fn fund_pool_until_settlement() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let contract_id = AccountId::from([0x42; 32]);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(contract_id);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = new_event(10);
+            signup(&mut contract, accounts.bob);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(5);
+            assert_eq!(
+                contract.fund_pool(20),
+                Err(HandshakeError::InsufficientPayment)
+            );
+            fund_pool(&mut contract, accounts.eve, 20);
+            assert!(recorded_events().iter().any(|event| matches!(
+                event,
+                Event::PoolFunded(PoolFunded { sponsor, value: 20 }) if *sponsor == accounts.eve
+            )));
+
+            advance_to(&mut contract, Phase::Settlement);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(20);
+            assert_eq!(contract.fund_pool(20), Err(HandshakeError::WrongPhase));
+
+            // nobody networked, so the sponsor pool is left to the organizer
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(contract_id, 30);
+            assert_eq!(contract.claimable(accounts.bob), 10);
+            assert_eq!(contract.organizer_share(), 20);
+        }
+
+        #[ink::test]
+        fn claims_fee_and_sweep_add_up_to_deposits() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let contract_id = AccountId::from([0x42; 32]);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(contract_id);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = new_event(10);
+            assert_eq!(
+                contract.set_organizer_fee(BASIS_POINTS + 1),
+                Err(HandshakeError::InvalidFee)
+            );
+            assert_eq!(contract.set_organizer_fee(500), Ok(()));
+            for account in [accounts.bob, accounts.charlie, accounts.django] {
+                signup(&mut contract, account);
+            }
+            fund_pool(&mut contract, accounts.eve, 21);
+            network(&mut contract);
+            advance_to(&mut contract, Phase::Settlement);
+            // the off-chain engine does not move the transferred value itself
+            let deposits = 51;
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+                contract_id,
+                deposits,
+            );
+
+            let mut paid = 0;
+            for account in [accounts.bob, accounts.charlie] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(account);
+                paid += contract.claim().unwrap();
+            }
+            assert_eq!(paid, 23 + 11);
+            assert_eq!(contract.claimable(accounts.django), 11);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                contract.sweep_unclaimed(),
+                Err(HandshakeError::ClaimWindowOpen)
+            );
+            paid += contract.withdraw_organizer_share().unwrap();
+            assert_eq!(paid, 23 + 11 + 4);
+
+            advance_to(&mut contract, Phase::Closed);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(contract.claim(), Err(HandshakeError::EventClosed));
+            assert_eq!(contract.sweep_unclaimed(), Err(HandshakeError::NotOwner));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            paid += contract.sweep_unclaimed().unwrap();
+
+            assert_eq!(paid, deposits);
+            assert_eq!(
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(contract_id),
+                Ok(0)
+            );
+            let events = recorded_events();
+            assert!(events.iter().any(|event| matches!(
+                event,
+                Event::OrganizerShareWithdrawn(OrganizerShareWithdrawn { owner, value: 4 })
+                    if *owner == accounts.alice
+            )));
+            assert!(events.iter().any(|event| matches!(
+                event,
+                Event::UnclaimedSwept(UnclaimedSwept { owner, value: 13 })
+                    if *owner == accounts.alice
+            )));
+        }
+
+        #[ink::test]
+        fn sweep_waits_for_claim_deadline() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let contract_id = AccountId::from([0x42; 32]);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(contract_id);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = new_event(10);
+            assert_eq!(
+                contract.set_schedule(10, 20, 20 + MIN_CLAIM_WINDOW - 1),
+                Err(HandshakeError::InvalidSchedule)
+            );
+            assert_eq!(
+                contract.sweep_unclaimed(),
+                Err(HandshakeError::ClaimWindowOpen)
+            );
+            for account in [accounts.bob, accounts.charlie] {
+                signup(&mut contract, account);
+            }
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(contract_id, 20);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.advance_phase(), Ok(Phase::Networking));
+            assert_eq!(contract.advance_phase(), Ok(Phase::Settlement));
+            assert_eq!(contract.schedule().2, 1_000 + MIN_CLAIM_WINDOW);
+
+            // the owner can neither end the claims early nor sweep the stakes
+            assert_eq!(contract.close_event(), Err(HandshakeError::ClaimWindowOpen));
+            assert_eq!(
+                contract.advance_phase(),
+                Err(HandshakeError::ClaimWindowOpen)
+            );
+            assert_eq!(
+                contract.sweep_unclaimed(),
+                Err(HandshakeError::ClaimWindowOpen)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.claim(), Ok(10));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.claim(), Ok(10));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.close_event(), Ok(()));
+            assert_eq!(
+                contract.sweep_unclaimed(),
+                Err(HandshakeError::ClaimWindowOpen)
+            );
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(
+                1_000 + MIN_CLAIM_WINDOW,
+            );
+            assert_eq!(contract.sweep_unclaimed(), Ok(0));
+        }
+
+        #[cfg(feature = "attestations")]
+        fn keypair(seed: u8) -> schnorrkel::Keypair {
+            schnorrkel::MiniSecretKey::from_bytes(&[seed; 32])
+                .unwrap()
+                .expand_to_keypair(schnorrkel::ExpansionMode::Ed25519)
+        }
 
-    #[ink(storage)]
-    #[derive(Default, Storage)]
-    pub struct Handshake {
-        #[storage_field]
-        psp34: psp34::Data,
-        next_id: u8,
-        ticket_price: Balance,
-        accounts: ink::prelude::vec::Vec<AccountId>,
-        #[storage_field]
-        handshakes: ink::prelude::vec::Vec<(AccountId, AccountId)>,
-    }
+        #[cfg(feature = "attestations")]
+        fn account_of(keypair: &schnorrkel::Keypair) -> AccountId {
+            AccountId::from(keypair.public.to_bytes())
+        }
 
-    impl Handshake {
-        #[ink(constructor)]
-        pub fn new(ticket_price: Balance) -> Self {
-            Self {
-                ticket_price,
-                ..Default::default()
+        #[cfg(feature = "attestations")]
+        fn attest(
+            contract: &Handshake,
+            a: &schnorrkel::Keypair,
+            b: &schnorrkel::Keypair,
+        ) -> Attestation {
+            let message = contract.attestation_message(account_of(a), account_of(b));
+            Attestation {
+                a: account_of(a),
+                b: account_of(b),
+                signature_a: a.sign_simple(b"substrate", &message).to_bytes(),
+                signature_b: b.sign_simple(b"substrate", &message).to_bytes(),
             }
         }
 
-        #[ink(message, payable)]
-        pub fn signup(&mut self) -> Result<(), PSP34Error> {
-            assert!(
-                self.ticket_price <= Self::env().transferred_value(),
-                "payment was not the ticket price"
+        #[cfg(feature = "attestations")]
+        #[ink::test]
+        fn attestations_confirm_handshakes() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let (first, second, third) = (keypair(1), keypair(2), keypair(3));
+            let mut contract = new_event(7);
+            for keypair in [&first, &second, &third] {
+                signup(&mut contract, account_of(keypair));
+            }
+            advance_to(&mut contract, Phase::Networking);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(account_of(&second));
+            assert_eq!(contract.handshake(account_of(&first)), Ok(()));
+
+            let batch = vec![
+                attest(&contract, &first, &second),
+                attest(&contract, &third, &second),
+            ];
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(contract.submit_attestations(batch.clone()), Ok(2));
+            assert_eq!(contract.num_handshakes(), Ok(2));
+            assert_eq!(contract.num_active_accounts(), Ok(3));
+            assert_eq!(contract.handshake_count(account_of(&second)), 2);
+            assert_eq!(
+                contract.open_handshakes_of(account_of(&first), 0, 10),
+                vec![]
             );
-            let caller = Self::env().caller();
-            psp34::Internal::_mint_to(self, caller, Id::U8(self.next_id))?;
-            self.next_id += 1;
-            self.accounts.push(caller);
-            Ok(())
-        }
 
-        #[ink(message)]
-        pub fn num_accounts(&self) -> Result<u32, PSP34Error> {
-            Ok(self.accounts.len().try_into().unwrap())
+            // submitting the same attestations again changes nothing
+            assert_eq!(contract.submit_attestations(batch), Ok(0));
+            assert_eq!(contract.num_handshakes(), Ok(2));
         }
 
-        #[ink(message)]
-        pub fn handshake(&mut self, other: AccountId) -> Result<(), PSP34Error> {
-            let executive = Self::env().caller();
-            self.handshakes.push((executive, other));
-            Ok(())
-        }
+        #[cfg(feature = "attestations")]
+        #[ink::test]
+        fn attestations_need_both_signatures() {
+            let (first, second, third) = (keypair(1), keypair(2), keypair(3));
+            let mut contract = new_event(7);
+            for keypair in [&first, &second, &third] {
+                signup(&mut contract, account_of(keypair));
+            }
+            let attestation = attest(&contract, &first, &second);
+            assert_eq!(
+                contract.submit_attestations(vec![attestation.clone()]),
+                Err(HandshakeError::WrongPhase)
+            );
+            advance_to(&mut contract, Phase::Networking);
 
-        #[ink(message)]
-        pub fn num_handshakes(&self) -> Result<u32, PSP34Error> {
-            Ok(self.handshakes.len().try_into().unwrap())
+            let forged = Attestation {
+                signature_b: attest(&contract, &first, &third).signature_b,
+                ..attestation.clone()
+            };
+            assert_eq!(
+                contract.submit_attestations(vec![forged]),
+                Err(HandshakeError::InvalidSignature)
+            );
+            let unsigned = Attestation {
+                a: account_of(&third),
+                ..attestation.clone()
+            };
+            assert_eq!(
+                contract.submit_attestations(vec![unsigned]),
+                Err(HandshakeError::InvalidSignature)
+            );
+            assert_eq!(
+                contract.submit_attestations(vec![attestation; MAX_BATCH_SIZE as usize + 1]),
+                Err(HandshakeError::BatchTooLarge)
+            );
+            assert_eq!(contract.num_handshakes(), Ok(0));
         }
 
-        #[ink(message)]
-        pub fn open_handshakes(&self) -> Result<Vec<AccountId>, PSP34Error> {
-            let caller = Self::env().caller();
-            let other_handshakes: Vec<AccountId> = self
-                .handshakes
-                .iter()
-                .filter(|(_, other)| other == &caller)
-                .map(|(executive, _)| *executive)
-                .collect();
-            Ok(other_handshakes)
+        #[ink::test]
+        fn leaderboard_ranks_by_handshakes() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let attendees = [
+                accounts.alice,
+                accounts.bob,
+                accounts.charlie,
+                accounts.django,
+                accounts.eve,
+                accounts.frank,
+            ];
+            let mut contract = new_event(7);
+            for account in attendees {
+                signup(&mut contract, account);
+            }
+            advance_to(&mut contract, Phase::Networking);
+            assert_eq!(contract.leaderboard(0, 10), vec![]);
+            assert_eq!(contract.num_active_accounts(), Ok(0));
+
+            let pairs = [
+                (accounts.bob, accounts.charlie),
+                (accounts.bob, accounts.django),
+                (accounts.eve, accounts.frank),
+                (accounts.eve, accounts.bob),
+                (accounts.charlie, accounts.django),
+                (accounts.frank, accounts.charlie),
+                (accounts.charlie, accounts.eve),
+            ];
+            for (from, to) in pairs {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(from);
+                assert_eq!(contract.handshake(to), Ok(()));
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(to);
+                assert_eq!(contract.confirm_handshake(from), Ok(()));
+
+                let leaderboard = contract.leaderboard(0, 10);
+                assert!(leaderboard.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+                for (position, (account, count)) in leaderboard.iter().enumerate() {
+                    assert_eq!(*count, contract.handshake_count(*account));
+                    assert_eq!(contract.rank_of(*account), Some(position as u32));
+                }
+            }
+
+            let leaderboard = contract.leaderboard(0, 10);
+            assert_eq!(leaderboard.len(), 5);
+            assert_eq!(contract.num_active_accounts(), Ok(5));
+            assert_eq!(leaderboard[0], (accounts.charlie, 4));
+            assert_eq!(leaderboard[1].1, 3);
+            assert_eq!(leaderboard[4].1, 2);
+            assert_eq!(contract.rank_of(accounts.alice), None);
+            assert_eq!(contract.leaderboard(4, 10), leaderboard[4..].to_vec());
         }
 
-        #[ink(message)]
-        pub fn get_primary_domains(
-            &self,
-            router_addr: AccountId,
-            account: AccountId,
-            tld: Option<String>,
-        ) -> Vec<(AccountId, String)> {
-            let router: ink::contract_ref!(AznsContract) = router_addr.into();
+        #[ink::test]
+        fn events_are_emitted() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = new_event(7);
+            signup(&mut contract, accounts.alice);
+            signup(&mut contract, accounts.bob);
+            advance_to(&mut contract, Phase::Networking);
 
-            router.get_primary_domains(account, tld)
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.handshake(accounts.bob), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.confirm_handshake(accounts.alice), Ok(()));
+            advance_to(&mut contract, Phase::Settlement);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.claim(), Ok(7));
+
+            let events = recorded_events();
+            assert_eq!(events.len(), 5);
+            assert!(matches!(
+                &events[0],
+                Event::SignedUp(SignedUp { account, value: 7, .. }) if *account == accounts.alice
+            ));
+            assert!(matches!(
+                &events[2],
+                Event::HandshakeRequested(HandshakeRequested { from, to })
+                    if *from == accounts.alice && *to == accounts.bob
+            ));
+            assert!(matches!(
+                &events[3],
+                Event::HandshakeConfirmed(HandshakeConfirmed { from, to })
+                    if *from == accounts.alice && *to == accounts.bob
+            ));
+            assert!(matches!(
+                &events[4],
+                Event::PaidOut(PaidOut { account, value: 7 }) if *account == accounts.bob
+            ));
         }
 
-        // TODO: calculation of value based on handshake
-        #[ink(message)]
-        pub fn payout(&mut self, value: Balance) {
-            ink::env::debug_println!("requested value: {}", value);
-            ink::env::debug_println!("contract balance: {}", self.env().balance());
+        #[ink::test]
+        fn paginated_getters() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = new_event(7);
+            let attendees = [
+                accounts.alice,
+                accounts.bob,
+                accounts.charlie,
+                accounts.django,
+            ];
+            for account in attendees {
+                signup(&mut contract, account);
+            }
+            assert_eq!(contract.accounts(0, 10), attendees.to_vec());
+            assert_eq!(
+                contract.accounts(1, 2),
+                vec![accounts.bob, accounts.charlie]
+            );
+            assert_eq!(contract.accounts(4, 2), vec![]);
+            advance_to(&mut contract, Phase::Networking);
 
-            assert!(value <= self.env().balance(), "insufficient funds!");
+            for account in [accounts.bob, accounts.charlie, accounts.django] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(account);
+                assert_eq!(contract.handshake(accounts.alice), Ok(()));
+            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.confirm_handshake(accounts.bob), Ok(()));
+            // the last request takes the slot of the confirmed one
+            assert_eq!(
+                contract.open_handshakes(),
+                Ok(vec![accounts.django, accounts.charlie])
+            );
+            assert_eq!(
+                contract.open_handshakes_of(accounts.alice, 1, 10),
+                vec![accounts.charlie]
+            );
+        }
 
-            if self.env().transfer(self.env().caller(), value).is_err() {
-                panic!(
-                    "requested transfer failed. this can be the case if the contract does not\
-                     have sufficient free funds or if the transfer would have brought the\
-                     contract's balance below minimum balance."
-                )
+        #[ink::test]
+        fn open_handshakes_are_capped() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = new_event(0);
+            signup(&mut contract, accounts.alice);
+            let senders: Vec<AccountId> = (0..MAX_PAGE_SIZE + 20)
+                .map(|i| {
+                    let mut account = [0xAA; 32];
+                    account[..4].copy_from_slice(&i.to_le_bytes());
+                    AccountId::from(account)
+                })
+                .collect();
+            for sender in &senders {
+                signup(&mut contract, *sender);
+            }
+            advance_to(&mut contract, Phase::Networking);
+            for sender in &senders {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(*sender);
+                assert_eq!(contract.handshake(accounts.alice), Ok(()));
             }
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let open = contract.open_handshakes().unwrap();
+            assert_eq!(open, senders[..MAX_PAGE_SIZE as usize].to_vec());
+            assert_eq!(
+                contract.open_handshakes_of(accounts.alice, MAX_PAGE_SIZE, MAX_PAGE_SIZE),
+                senders[MAX_PAGE_SIZE as usize..].to_vec()
+            );
         }
-    }
 
-    #[cfg(test)]
-    mod tests {
-        use super::*;
         #[ink::test]
-        fn new_contract_works() {
-            let contract = Handshake::new(7);
-            assert_eq!(contract.num_accounts(), Ok(0));
-            assert_eq!(contract.num_handshakes(), Ok(0));
+        fn connection_graph_queries() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = new_event(7);
+            for account in [
+                accounts.alice,
+                accounts.bob,
+                accounts.charlie,
+                accounts.django,
+            ] {
+                signup(&mut contract, account);
+            }
+            advance_to(&mut contract, Phase::Networking);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            for account in [accounts.bob, accounts.charlie, accounts.django] {
+                assert_eq!(contract.handshake(account), Ok(()));
+            }
+            assert_eq!(
+                contract.outgoing_pending(accounts.alice, 0, 10),
+                vec![accounts.bob, accounts.charlie, accounts.django]
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.confirm_handshake(accounts.alice), Ok(()));
+            // the last request takes the slot of the confirmed one
+            assert_eq!(
+                contract.outgoing_pending(accounts.alice, 0, 10),
+                vec![accounts.django, accounts.charlie]
+            );
+            assert_eq!(
+                contract.outgoing_pending(accounts.alice, 1, 1),
+                vec![accounts.charlie]
+            );
+            assert_eq!(contract.outgoing_pending(accounts.bob, 0, 10), vec![]);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.confirm_handshake(accounts.alice), Ok(()));
+            assert_eq!(
+                contract.outgoing_pending(accounts.alice, 0, 10),
+                vec![accounts.django]
+            );
+            assert_eq!(
+                contract.connections_of(accounts.alice, 0, 10),
+                vec![accounts.bob, accounts.charlie]
+            );
+            assert_eq!(
+                contract.connections_of(accounts.alice, 1, 10),
+                vec![accounts.charlie]
+            );
+            assert_eq!(
+                contract.connections_of(accounts.charlie, 0, 10),
+                vec![accounts.alice]
+            );
+            assert_eq!(contract.handshake_count(accounts.alice), 2);
+            assert!(contract.are_connected(accounts.alice, accounts.bob));
+            assert!(contract.are_connected(accounts.bob, accounts.alice));
+            assert!(!contract.are_connected(accounts.alice, accounts.django));
+            assert!(!contract.are_connected(accounts.bob, accounts.charlie));
         }
     }
 
@@ -161,6 +2750,7 @@ pub mod handshake {
         use ink_e2e::subxt::tx::Signer;
         use ink_e2e::subxt::utils::AccountId32;
         use ink_e2e::{build_message, Keypair, PolkadotConfig};
+        use psp22_mock::psp22_mock::Psp22MockRef;
 
         type ContractRef = HandshakeRef;
 
@@ -173,7 +2763,7 @@ pub mod handshake {
 
         #[ink_e2e::test]
         async fn account_signup(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
-            let constructor = ContractRef::new(7);
+            let constructor = ContractRef::new(7, String::from("Handshake"), String::from("HSK"));
             let address = client
                 .instantiate("handshake", &ink_e2e::alice(), constructor, 0, None)
                 .await
@@ -207,13 +2797,31 @@ pub mod handshake {
 
         #[ink_e2e::test]
         async fn count_handshakes(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
-            let constructor = ContractRef::new(7);
+            let constructor = ContractRef::new(7, String::from("Handshake"), String::from("HSK"));
             let address = client
                 .instantiate("handshake", &ink_e2e::alice(), constructor, 0, None)
                 .await
                 .expect("instantiate failed")
                 .account_id;
 
+            for signer in [ink_e2e::alice(), ink_e2e::bob()] {
+                let _msg = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.signup());
+                client
+                    .call(&signer, _msg, 7, None)
+                    .await
+                    .expect("signup failed");
+            }
+
+            {
+                let _msg = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.advance_phase());
+                client
+                    .call(&ink_e2e::alice(), _msg, 0, None)
+                    .await
+                    .expect("advance phase failed");
+            }
+
             let result = {
                 let _msg = build_message::<ContractRef>(address.clone())
                     .call(|contract| contract.handshake(address_of(&ink_e2e::bob())));
@@ -225,6 +2833,17 @@ pub mod handshake {
 
             assert!(matches!(result.return_value(), Ok(())));
 
+            let result = {
+                let _msg = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.confirm_handshake(address_of(&ink_e2e::alice())));
+                client
+                    .call(&ink_e2e::bob(), _msg, 0, None)
+                    .await
+                    .expect("confirm handshake failed")
+            };
+
+            assert!(matches!(result.return_value(), Ok(())));
+
             let num_accounts = {
                 let _msg = build_message::<ContractRef>(address.clone())
                     .call(|contract| contract.num_handshakes());
@@ -239,9 +2858,167 @@ pub mod handshake {
             Ok(())
         }
 
+        #[ink_e2e::test]
+        async fn handshake_weight_is_bounded(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let constructor = ContractRef::new(7, String::from("Handshake"), String::from("HSK"));
+            let address = client
+                .instantiate("handshake", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // weights are compared after a few and after a few hundred attendees
+            const FEW: usize = 10;
+            const MANY: usize = 300;
+            let mut attendees = vec![ink_e2e::alice()];
+            for _ in 1..MANY {
+                attendees.push(
+                    client
+                        .create_and_fund_account(&ink_e2e::alice(), 1_000_000_000_000_000)
+                        .await,
+                );
+            }
+            let mut signup_weights = Vec::new();
+            for signer in attendees.iter() {
+                let _msg = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.signup());
+                let dry_run = client.call_dry_run(signer, &_msg, 7, None).await;
+                signup_weights.push(dry_run.exec_result.gas_consumed.ref_time());
+                client
+                    .call(signer, _msg, 7, None)
+                    .await
+                    .expect("signup failed");
+            }
+
+            {
+                let _msg = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.advance_phase());
+                client
+                    .call(&ink_e2e::alice(), _msg, 0, None)
+                    .await
+                    .expect("advance phase failed");
+            }
+
+            // everybody else sends a handshake to alice
+            let mut weights = Vec::new();
+            for signer in attendees.iter().skip(1) {
+                let _msg = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.handshake(address_of(&ink_e2e::alice())));
+                let dry_run = client.call_dry_run(signer, &_msg, 0, None).await;
+                weights.push(dry_run.exec_result.gas_consumed.ref_time());
+                client
+                    .call(signer, _msg, 0, None)
+                    .await
+                    .expect("handshake failed");
+            }
+
+            let bounded = |weights: &[u64]| {
+                let (few, many) = (weights[FEW - 1], weights[weights.len() - 1]);
+                many <= few + few / 10
+            };
+            assert!(
+                bounded(&signup_weights),
+                "signup weight grows with attendees: {:?}",
+                signup_weights
+            );
+            // the last handshake is appended to an incoming list of MANY - 2 requests
+            assert!(
+                bounded(&weights),
+                "handshake weight grows with pending requests: {:?}",
+                weights
+            );
+
+            Ok(())
+        }
+
+        #[cfg(feature = "attestations")]
+        #[ink_e2e::test]
+        async fn submit_attestations(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let constructor = ContractRef::new(7, String::from("Handshake"), String::from("HSK"));
+            let address = client
+                .instantiate("handshake", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            for signer in [ink_e2e::alice(), ink_e2e::bob(), ink_e2e::charlie()] {
+                let _msg = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.signup());
+                client
+                    .call(&signer, _msg, 7, None)
+                    .await
+                    .expect("signup failed");
+            }
+
+            {
+                let _msg = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.advance_phase());
+                client
+                    .call(&ink_e2e::alice(), _msg, 0, None)
+                    .await
+                    .expect("advance phase failed");
+            }
+
+            // bob and charlie sign off-chain, the way the browser extension does
+            let (bob, charlie) = (ink_e2e::bob(), ink_e2e::charlie());
+            let message = {
+                let _msg = build_message::<ContractRef>(address.clone()).call(|contract| {
+                    contract.attestation_message(address_of(&bob), address_of(&charlie))
+                });
+                client
+                    .call_dry_run(&ink_e2e::alice(), &_msg, 0, None)
+                    .await
+                    .return_value()
+            };
+            let attestation = Attestation {
+                a: address_of(&bob),
+                b: address_of(&charlie),
+                signature_a: bob.sign(&message).0,
+                signature_b: charlie.sign(&message).0,
+            };
+
+            // the host function rejects a signature by the wrong account
+            let forged = Attestation {
+                signature_b: ink_e2e::dave().sign(&message).0,
+                ..attestation.clone()
+            };
+            let result = {
+                let _msg = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.submit_attestations(vec![forged.clone()]));
+                client
+                    .call_dry_run(&ink_e2e::alice(), &_msg, 0, None)
+                    .await
+                    .return_value()
+            };
+            assert_eq!(result, Err(HandshakeError::InvalidSignature));
+
+            // alice relays the attestation
+            let result = {
+                let _msg = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.submit_attestations(vec![attestation.clone()]));
+                client
+                    .call(&ink_e2e::alice(), _msg, 0, None)
+                    .await
+                    .expect("submit attestations failed")
+            };
+            assert_eq!(result.return_value(), Ok(1));
+
+            let num_handshakes = {
+                let _msg = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.num_handshakes());
+                client
+                    .call_dry_run(&ink_e2e::alice(), &_msg, 0, None)
+                    .await
+                    .return_value()
+            };
+            assert_eq!(num_handshakes, Ok(1));
+
+            Ok(())
+        }
+
         #[ink_e2e::test]
         async fn cannot_signup_without_payment(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
-            let constructor = ContractRef::new(7);
+            let constructor = ContractRef::new(7, String::from("Handshake"), String::from("HSK"));
             let address = client
                 .instantiate("handshake", &ink_e2e::alice(), constructor, 0, None)
                 .await
@@ -251,16 +3028,116 @@ pub mod handshake {
             let result = {
                 let _msg = build_message::<ContractRef>(address.clone())
                     .call(|contract| contract.signup());
-                client.call(&ink_e2e::alice(), _msg, 0, None).await
+                client
+                    .call_dry_run(&ink_e2e::alice(), &_msg, 0, None)
+                    .await
+                    .return_value()
             };
 
-            if let Err(ink_e2e::Error::CallDryRun(dry_run)) = result {
-                let debug_message = String::from_utf8_lossy(&dry_run.debug_message);
-                assert!(debug_message.contains("payment was not the ticket price"))
-            } else {
-                panic!("Signup without payment should fail")
+            assert_eq!(result, Err(HandshakeError::InsufficientPayment));
+
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp22-mock/Cargo.toml")]
+        async fn stake_in_psp22_token(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let constructor = Psp22MockRef::new(1000);
+            let token = client
+                .instantiate("psp22_mock", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate token failed")
+                .account_id;
+            {
+                let _msg = build_message::<Psp22MockRef>(token.clone())
+                    .call(|token| token.transfer(address_of(&ink_e2e::bob()), 100, Vec::new()));
+                client
+                    .call(&ink_e2e::alice(), _msg, 0, None)
+                    .await
+                    .expect("token transfer failed");
+            }
+
+            let constructor = ContractRef::with_token(
+                7,
+                String::from("Handshake"),
+                String::from("HSK"),
+                token.clone(),
+            );
+            let address = client
+                .instantiate("handshake", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // signing up without an allowance fails
+            let result = {
+                let _msg = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.signup());
+                client
+                    .call_dry_run(&ink_e2e::bob(), &_msg, 0, None)
+                    .await
+                    .return_value()
+            };
+            assert_eq!(
+                result,
+                Err(HandshakeError::PSP22Error(
+                    PSP22Error::InsufficientAllowance
+                ))
+            );
+
+            for signer in [ink_e2e::alice(), ink_e2e::bob()] {
+                let _msg = build_message::<Psp22MockRef>(token.clone())
+                    .call(|token| token.approve(address.clone(), 7));
+                client
+                    .call(&signer, _msg, 0, None)
+                    .await
+                    .expect("approve failed");
+                let _msg = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.signup());
+                client
+                    .call(&signer, _msg, 0, None)
+                    .await
+                    .expect("signup failed");
+            }
+
+            let staked = {
+                let _msg = build_message::<Psp22MockRef>(token.clone())
+                    .call(|token| token.balance_of(address.clone()));
+                client
+                    .call_dry_run(&ink_e2e::alice(), &_msg, 0, None)
+                    .await
+                    .return_value()
+            };
+            assert_eq!(staked, 14);
+
+            for _ in 0..2 {
+                let _msg = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.advance_phase());
+                client
+                    .call(&ink_e2e::alice(), _msg, 0, None)
+                    .await
+                    .expect("advance phase failed");
             }
 
+            let result = {
+                let _msg =
+                    build_message::<ContractRef>(address.clone()).call(|contract| contract.claim());
+                client
+                    .call(&ink_e2e::bob(), _msg, 0, None)
+                    .await
+                    .expect("claim failed")
+            };
+            assert_eq!(result.return_value(), Ok(7));
+
+            let balance = {
+                let _msg = build_message::<Psp22MockRef>(token.clone())
+                    .call(|token| token.balance_of(address_of(&ink_e2e::bob())));
+                client
+                    .call_dry_run(&ink_e2e::bob(), &_msg, 0, None)
+                    .await
+                    .return_value()
+            };
+            assert_eq!(balance, 100);
+
             Ok(())
         }
     }
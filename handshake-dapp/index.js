@@ -16,8 +16,8 @@ let getPolkadotJsExtensionMod = (() => {
 })();
 
 // Load the contract metadata from a JSON file
-async function loadContractMetadata() {
-  const response = await fetch("res/handshake.json");
+async function loadContractMetadata(file = "res/handshake.json") {
+  const response = await fetch(file);
 
   if (!response.ok) {
     throw new Error("Failed to fetch the contract metadata");
@@ -58,13 +58,21 @@ async function getAccounts() {
 
 
 async function queryContract(contractAddress, queryFunction, ...args) {
+  return await queryWithMetadata("res/handshake.json", contractAddress, queryFunction, ...args);
+}
+
+async function queryRegistry(registryAddress, queryFunction, ...args) {
+  return await queryWithMetadata("res/registry.json", registryAddress, queryFunction, ...args);
+}
+
+async function queryWithMetadata(metadataFile, contractAddress, queryFunction, ...args) {
   const { ContractPromise } = await import(
     "https://cdn.jsdelivr.net/npm/@polkadot/api-contract@10.9.1/+esm"
   );
 
   const { api } = await initApi();
 
-  const metadata = await loadContractMetadata();
+  const metadata = await loadContractMetadata(metadataFile);
   const contract = new ContractPromise(api, metadata, contractAddress);
 
   const { BN, BN_ONE } = await import(
@@ -93,10 +101,44 @@ async function queryContract(contractAddress, queryFunction, ...args) {
     console.error("Error", result.asErr);
     throw new Error(result.asErr);
   }
-  return output.toHuman().Ok.Ok;
+  return output.toHuman().Ok;
+}
+
+// Unwrap the value of a message returning a `Result`.
+// Messages return a `HandshakeError` variant on failure.
+function unwrapResult(value) {
+  if (value.Err !== undefined) {
+    throw new Error(JSON.stringify(value.Err));
+  }
+  return value.Ok;
 }
 
-async function doHandshake(contractAddress, source, senderAddress, destinationAddress) {
+// Strips the thousands separators `toHuman` puts into numbers, e.g. "1,000".
+function plainNumber(value) {
+  return String(value).replace(/,/g, "");
+}
+
+// Sends a handshake, attaching the note and the contact if they are not empty.
+// The contact is encrypted to the contact key the destination published.
+async function doHandshake(contractAddress, source, senderAddress, destinationAddress, note, contact) {
+  if (!note && !contact) {
+    return await sendTransaction(contractAddress, source, senderAddress, "handshake", destinationAddress);
+  }
+  const encrypted = contact
+    ? await encryptContact(contractAddress, destinationAddress, contact)
+    : null;
+  return await sendTransaction(
+    contractAddress,
+    source,
+    senderAddress,
+    "handshakeWithMetadata",
+    destinationAddress,
+    { note, contact: encrypted }
+  );
+}
+
+// Signs and sends a call of `txFunction` with the account from the browser extension.
+async function sendTransaction(contractAddress, source, senderAddress, txFunction, ...args) {
   const { ContractPromise } = await import(
     "https://cdn.jsdelivr.net/npm/@polkadot/api-contract@10.9.1/+esm"
   );
@@ -123,14 +165,110 @@ async function doHandshake(contractAddress, source, senderAddress, destinationAd
   const extensionMod = await getPolkadotJsExtensionMod();
   const injector = await extensionMod.web3FromSource(source);
 
-  const result = await contract.tx["handshake"]({
-    gasLimit,
-    storageDepositLimit,
-  }, destinationAddress).signAndSend(senderAddress, { signer: injector.signer });
-  console.log("handshake transaction result", result);
+  const options = { gasLimit, storageDepositLimit };
+  const tx = contract.tx[txFunction](options, ...args);
+  const result = await tx.signAndSend(senderAddress, { signer: injector.signer });
+  console.log(`${txFunction} transaction result`, result);
   return result.toHuman();
 }
 
+let getNacl = async () =>
+  (await import("https://cdn.jsdelivr.net/npm/tweetnacl@1.0.3/+esm")).default;
+
+// The x25519 key pair `accountAddress` receives encrypted contacts with.
+// The secret key never leaves the browser, only the public key is published.
+async function contactKeyPair(accountAddress) {
+  const nacl = await getNacl();
+  const { hexToU8a, u8aToHex } = await import(
+    "https://cdn.jsdelivr.net/npm/@polkadot/util@12.4.1/+esm"
+  );
+  const storageKey = `handshake-contact-key-${accountAddress}`;
+  const stored = window.localStorage.getItem(storageKey);
+  if (stored) {
+    return nacl.box.keyPair.fromSecretKey(hexToU8a(stored));
+  }
+  const keyPair = nacl.box.keyPair();
+  window.localStorage.setItem(storageKey, u8aToHex(keyPair.secretKey));
+  return keyPair;
+}
+
+// Returns the contact key `accountAddress` published as hex, or an empty string.
+async function fetchContactKey(contractAddress, accountAddress) {
+  const { u8aToHex } = await import(
+    "https://cdn.jsdelivr.net/npm/@polkadot/util@12.4.1/+esm"
+  );
+  const key = await queryContract(contractAddress, "contactKey", accountAddress);
+  if (!key) {
+    return "";
+  }
+  // fixed size byte arrays are either shown as hex or as a list of numbers
+  return Array.isArray(key) ? u8aToHex(Uint8Array.from(key.map(Number))) : key;
+}
+
+// Whether the contact key published for the account is the one kept in this browser.
+async function hasContactKey(contractAddress, accountAddress) {
+  const { u8aToHex } = await import(
+    "https://cdn.jsdelivr.net/npm/@polkadot/util@12.4.1/+esm"
+  );
+  const published = await fetchContactKey(contractAddress, accountAddress);
+  const { publicKey } = await contactKeyPair(accountAddress);
+  return published === u8aToHex(publicKey);
+}
+
+// Publishes the contact key of this browser, so others can send the account their contact.
+async function publishContactKey(contractAddress, source, accountAddress) {
+  const { u8aToHex } = await import(
+    "https://cdn.jsdelivr.net/npm/@polkadot/util@12.4.1/+esm"
+  );
+  const { publicKey } = await contactKeyPair(accountAddress);
+  return await sendTransaction(
+    contractAddress,
+    source,
+    accountAddress,
+    "setContactKey",
+    u8aToHex(publicKey)
+  );
+}
+
+// Encrypts `contact` to the destination's contact key with an ephemeral key pair.
+// The payload is the ephemeral public key, the nonce and the box, as hex.
+async function encryptContact(contractAddress, destinationAddress, contact) {
+  const nacl = await getNacl();
+  const { hexToU8a, stringToU8a, u8aConcat, u8aToHex } = await import(
+    "https://cdn.jsdelivr.net/npm/@polkadot/util@12.4.1/+esm"
+  );
+  const key = await fetchContactKey(contractAddress, destinationAddress);
+  if (!key) {
+    throw new Error("The attendee has not enabled contact exchange yet");
+  }
+  const ephemeral = nacl.box.keyPair();
+  const nonce = nacl.randomBytes(nacl.box.nonceLength);
+  const box = nacl.box(stringToU8a(contact), nonce, hexToU8a(key), ephemeral.secretKey);
+  return u8aToHex(u8aConcat(ephemeral.publicKey, nonce, box));
+}
+
+// Returns the contact `fromAddress` sent to `toAddress` decrypted, or an empty string.
+async function fetchContact(contractAddress, fromAddress, toAddress) {
+  const nacl = await getNacl();
+  const { hexToU8a, u8aToString } = await import(
+    "https://cdn.jsdelivr.net/npm/@polkadot/util@12.4.1/+esm"
+  );
+  const metadata = await queryContract(contractAddress, "handshakeMetadata", fromAddress, toAddress);
+  if (!metadata || !metadata.contact) {
+    return "";
+  }
+  const payload = hexToU8a(metadata.contact);
+  const ephemeralKey = payload.subarray(0, nacl.box.publicKeyLength);
+  const nonce = payload.subarray(
+    nacl.box.publicKeyLength,
+    nacl.box.publicKeyLength + nacl.box.nonceLength
+  );
+  const box = payload.subarray(nacl.box.publicKeyLength + nacl.box.nonceLength);
+  const { secretKey } = await contactKeyPair(toAddress);
+  const contact = nacl.box.open(box, nonce, ephemeralKey, secretKey);
+  return contact ? u8aToString(contact) : "";
+}
+
 async function doAccountLookup(accountAddress) {
   const { SupportedChainId, resolveAddressToDomain } = await import(
     "https://cdn.jsdelivr.net/npm/@azns/resolver-core/+esm"
@@ -149,11 +287,50 @@ async function doAccountLookup(accountAddress) {
 }
 
 async function fetchNumAccounts(contractAddress) {
-  return await queryContract(contractAddress, "numAccounts");
+  return plainNumber(unwrapResult(await queryContract(contractAddress, "numAccounts")));
 }
 
 async function fetchNumHandshakes(contractAddress) {
-  return await queryContract(contractAddress, "numHandshakes");
+  return plainNumber(unwrapResult(await queryContract(contractAddress, "numHandshakes")));
+}
+
+async function fetchNumActiveAccounts(contractAddress) {
+  return plainNumber(unwrapResult(await queryContract(contractAddress, "numActiveAccounts")));
+}
+
+// Returns the ticket id of the account, or an empty string without a ticket.
+async function fetchTicketOf(contractAddress, accountAddress) {
+  const ticket = await queryContract(contractAddress, "ticketOf", accountAddress);
+  return ticket ? String(Object.values(ticket)[0]) : "";
+}
+
+// Returns a page of the leaderboard as JSON, `[{ address, handshakes }]` ranked by handshakes.
+async function fetchLeaderboard(contractAddress, offset, limit) {
+  const entries = await queryContract(contractAddress, "leaderboard", offset, limit);
+  return JSON.stringify(
+    entries.map(([address, handshakes]) => ({
+      address,
+      handshakes: Number(plainNumber(handshakes)),
+    }))
+  );
+}
+
+// Returns the addresses the account shares a confirmed handshake with as JSON.
+async function fetchConnectionsOf(contractAddress, accountAddress, offset, limit) {
+  const connections = await queryContract(
+    contractAddress,
+    "connectionsOf",
+    accountAddress,
+    offset,
+    limit
+  );
+  return JSON.stringify(connections);
+}
+
+// Returns a page of the events created in the registry as JSON, `[{ contract, organizer, name }]`.
+async function fetchEvents(registryAddress, offset, limit) {
+  const events = await queryRegistry(registryAddress, "events", offset, limit);
+  return JSON.stringify(events);
 }
 
 fetchNumAccounts("5C8iyAnGiuWN2Dc4MZJMwDkw8U6CGYAJsDru5zFK5bUFof4Y").catch(
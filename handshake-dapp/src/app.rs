@@ -1,4 +1,5 @@
 use crate::connect_to::ConnectTo;
+use crate::events::Events;
 use crate::leaderboard::Leaderboard;
 use crate::profile::Profile;
 use web_sys::window;
@@ -15,6 +16,8 @@ pub enum Route {
     Profile,
     #[at("/leaderboard")]
     Leaderboard,
+    #[at("/events")]
+    Events,
 }
 
 pub struct App;
@@ -37,34 +40,27 @@ impl Component for App {
     }
 }
 
+/// Returns the value of `name` in the query string of the current location.
+fn query_param(name: &str) -> Option<String> {
+    let query = window().and_then(|win| win.location().search().ok())?;
+    let query = query.trim_start_matches('?');
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.to_string())
+}
+
 fn switch(routes: Route) -> Html {
     match routes {
         Route::Connect => {
-            let id = match window().and_then(|win| win.location().search().ok()) {
-                Some(query) => {
-                    let query = query.trim_start_matches('?');
-                    let pairs: Vec<&str> = query.split('&').collect();
-                    pairs
-                        .iter()
-                        .filter_map(|&pair| {
-                            let mut split = pair.splitn(2, '=');
-                            let key = split.next()?;
-                            let value = split.next()?;
-                            if key == "id" {
-                                Some(value.to_string())
-                            } else {
-                                None
-                            }
-                        })
-                        .next()
-                        .unwrap_or_default()
-                }
-                None => String::new(),
-            };
+            let id = query_param("id").unwrap_or_default();
+            // the QR code carries the event the attendee signed up for
+            let event = query_param("event");
             html! {
             <div>
                 <Link<Route> to={Route::Home}> <button>{"Home"} </button></Link<Route>>
-                <ConnectTo id={id} />
+                <ConnectTo id={id} event={event} />
             </div> }
         }
         Route::Profile => {
@@ -83,12 +79,20 @@ fn switch(routes: Route) -> Html {
                 <Leaderboard />
             </div> }
         }
+        Route::Events => {
+            html! {
+            <div>
+                <Link<Route> to={Route::Home}> <button>{"Home"} </button></Link<Route>>
+                <Events />
+            </div> }
+        }
         Route::Home => {
             html! {
             <div>
                 <Link<Route> to={Route::Home}> <button>{"Home"} </button></Link<Route>>
                 <Link<Route> to={Route::Profile}> <button>{"My Profile"}</button></Link<Route>>
                 <Link<Route> to={Route::Leaderboard}> <button>{"Leaderboard"}</button></Link<Route>>
+                <Link<Route> to={Route::Events}> <button>{"Events"}</button></Link<Route>>
                 <img src="res/welcome.png" width="100%" height="100%" alt="welcome"/>
                 <a href="res/handshake.pdf" target="blank">{"Download Handshake Overview"}</a>
             </div> }
@@ -1,6 +1,21 @@
 use crate::services::get_azero_id;
 use yew::prelude::*;
 
+/// Links the AZERO.ID `domain` of an account, or shows its shortened `address` without one.
+pub fn account_name(domain: Option<&String>, address: &str) -> Html {
+    match domain {
+        Some(domain) => html! {
+            <a href={format!("https://{}.id", domain)} target="_blank">{domain}</a>
+        },
+        None if address.len() > 12 => html! {
+            {format!("{}…{}", &address[..6], &address[address.len() - 6..])}
+        },
+        None => html! {
+            {address}
+        },
+    }
+}
+
 pub enum Message {
     AzeroIdReceived(Result<String, anyhow::Error>),
 }
@@ -1,29 +1,87 @@
 use crate::address_button_component::AddressButtonComponent;
 use crate::azero_id::AzeroId;
 use crate::services::do_handshake;
-use crate::services::Account;
+use crate::services::{contract_address, is_known_event, select_event, Account};
+use web_sys::HtmlInputElement;
 use yew::prelude::*;
 
+/// Maximum length in bytes of the note, as enforced by the contract
+const MAX_NOTE_LENGTH: usize = 64;
+
+/// Maximum length in bytes of the contact, the contract's 256 bytes less the
+/// ephemeral key, nonce and authentication tag of the encryption
+const MAX_CONTACT_LENGTH: usize = 256 - 32 - 24 - 16;
+
 pub enum Message {
     AccountChanged(Account),
+    NoteChanged(String),
+    ContactChanged(String),
     DoHandshake,
+    HandshakeFailed(Account, String),
+    EventChecked(Result<String, String>),
 }
 
 pub struct ConnectTo {
     account: Option<Account>,
+    /// Note or tag attached to the handshake
+    note: String,
+    /// Why the note can not be sent
+    note_error: Option<String>,
+    /// Contact details, only readable by the counterparty
+    contact: String,
+    /// Why the contact can not be sent
+    contact_error: Option<String>,
+    /// Why the last handshake failed
+    handshake_error: Option<String>,
+    /// Whether the event of the link is checked, handshakes wait for it
+    event_checked: bool,
+    /// Why the event of the link was not selected
+    event_error: Option<String>,
 }
 
 #[derive(Properties, Clone, PartialEq)]
 pub struct Props {
     pub id: String,
+    /// Event contract of the link, only selected if the registry lists it
+    #[prop_or_default]
+    pub event: Option<String>,
+}
+
+impl ConnectTo {
+    /// Whether the event is settled and the metadata fits into the contract.
+    fn can_send(&self) -> bool {
+        self.event_checked && self.note_error.is_none() && self.contact_error.is_none()
+    }
 }
 
 impl Component for ConnectTo {
     type Message = Message;
     type Properties = Props;
 
-    fn create(_ctx: &Context<Self>) -> Self {
-        ConnectTo { account: None }
+    fn create(ctx: &Context<Self>) -> Self {
+        let event = ctx.props().event.clone();
+        let event_checked = event.is_none() || event.as_deref() == Some(&contract_address());
+        if let Some(event) = event.filter(|_| !event_checked) {
+            ctx.link().send_future(async move {
+                Message::EventChecked(match is_known_event(&event).await {
+                    Ok(true) => Ok(event),
+                    Ok(false) => Err(format!(
+                        "Unknown event {event}, staying on the current one."
+                    )),
+                    Err(err) => Err(format!("Failed to check the event: {err}")),
+                })
+            });
+        }
+        ConnectTo {
+            account: None,
+            note: String::new(),
+            note_error: None,
+            contact: String::new(),
+            contact_error: None,
+            handshake_error: None,
+            event_checked,
+            event_error: None,
+        }
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
@@ -32,28 +90,77 @@ impl Component for ConnectTo {
                 self.account = Some(account);
                 true
             }
+            Message::NoteChanged(note) => {
+                // maxlength counts characters, the contract counts bytes
+                let note_error = (note.trim().len() > MAX_NOTE_LENGTH).then(|| {
+                    format!("The note is too long, it can have at most {MAX_NOTE_LENGTH} bytes.")
+                });
+                let changed = note_error != self.note_error;
+                self.note = note;
+                self.note_error = note_error;
+                changed
+            }
+            Message::ContactChanged(contact) => {
+                let contact_error = (contact.trim().len() > MAX_CONTACT_LENGTH).then(|| {
+                    format!(
+                        "The contact is too long, it can have at most {MAX_CONTACT_LENGTH} bytes."
+                    )
+                });
+                let changed = contact_error != self.contact_error;
+                self.contact = contact;
+                self.contact_error = contact_error;
+                changed
+            }
+            Message::EventChecked(result) => {
+                match result {
+                    Ok(event) => select_event(&event),
+                    Err(error) => self.event_error = Some(error),
+                }
+                self.event_checked = true;
+                true
+            }
+            Message::HandshakeFailed(account, error) => {
+                self.account = Some(account);
+                self.handshake_error = Some(error);
+                true
+            }
             Message::DoHandshake => {
                 web_sys::console::log_1(&"Doing handshake".into());
                 web_sys::console::log_1(&format!("Account: {:?}", self.account).into());
+                if !self.can_send() {
+                    return false;
+                }
+                self.handshake_error = None;
                 if let Some(account) = &self.account {
                     let source = account.source.clone();
                     let sender_address = account.address.clone();
                     let destination_address = ctx.props().id.clone();
+                    let note = self.note.trim().to_string();
+                    let contact = self.contact.trim().to_string();
                     let account_clone = account.clone();
                     ctx.link().send_future(async move {
-                        match do_handshake(source, sender_address, destination_address).await {
+                        match do_handshake(
+                            source,
+                            sender_address,
+                            destination_address,
+                            note,
+                            contact,
+                        )
+                        .await
+                        {
                             Ok(response) => {
                                 web_sys::console::log_1(
                                     &format!("Handshake success: {}", response).into(),
                                 );
+                                Message::AccountChanged(account_clone)
                             }
                             Err(e) => {
                                 web_sys::console::log_1(
                                     &format!("Handshake error: {:?}", e).into(),
                                 );
+                                Message::HandshakeFailed(account_clone, e.to_string())
                             }
                         }
-                        Message::AccountChanged(account_clone)
                     });
                 }
                 true
@@ -63,6 +170,14 @@ impl Component for ConnectTo {
 
     fn view(&self, ctx: &Context<Self>) -> Html {
         let id = ctx.props().id.clone();
+        let on_note = ctx.link().callback(|event: InputEvent| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            Message::NoteChanged(input.value())
+        });
+        let on_contact = ctx.link().callback(|event: InputEvent| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            Message::ContactChanged(input.value())
+        });
         html! {
                    <div>
                    <AddressButtonComponent on_account={Some(ctx.link().callback(Message::AccountChanged))} on_address={Callback::noop()}/>
@@ -78,7 +193,21 @@ impl Component for ConnectTo {
                        </div>
                        <div id="id">
                        {id.clone()}</div>
-                       <button onclick={ctx.link().callback(|_| Message::DoHandshake)}>{"Handshake"}</button>
+                       <input id="note" type="text" maxlength={MAX_NOTE_LENGTH.to_string()} placeholder="Note, e.g. hiring or talk follow-up" oninput={on_note}/>
+                       if let Some(note_error) = &self.note_error {
+                           <div id="note-error">{note_error}</div>
+                       }
+                       <input id="contact" type="text" maxlength={MAX_CONTACT_LENGTH.to_string()} placeholder="Contact, only readable by them" oninput={on_contact}/>
+                       if let Some(contact_error) = &self.contact_error {
+                           <div id="contact-error">{contact_error}</div>
+                       }
+                       <button onclick={ctx.link().callback(|_| Message::DoHandshake)} disabled={!self.can_send()}>{"Handshake"}</button>
+                       if let Some(event_error) = &self.event_error {
+                           <div id="event-error">{event_error}</div>
+                       }
+                       if let Some(handshake_error) = &self.handshake_error {
+                           <div id="handshake-error">{handshake_error}</div>
+                       }
                    </div>
                    </div>
                }
@@ -9,3 +9,7 @@ pub const CONTRACT: &str = "5C8iyAnGiuWN2Dc4MZJMwDkw8U6CGYAJsDru5zFK5bUFof4Y";
 
 #[cfg(feature = "github")]
 pub const CONTRACT: &str = "5ECXVCvKkg3hafPo9YQEatdUgN5tZ3rtgrStHfHAwEErUgTv";
+
+/// Registry listing the events, `None` until one is deployed to the network.
+/// Without a registry the dapp stays on [`CONTRACT`].
+pub const REGISTRY: Option<&str> = None;
@@ -0,0 +1,104 @@
+use crate::services::{contract_address, get_events, select_event, EventEntry};
+use yew::prelude::*;
+
+/// Number of events shown
+const EVENTS_SIZE: u32 = 50;
+
+pub enum Message {
+    EventsReceived(Vec<EventEntry>),
+    Select(String),
+    Error(anyhow::Error),
+}
+
+pub struct Events {
+    events: Vec<EventEntry>,
+    /// address of the selected event
+    selected: String,
+    error: Option<String>,
+}
+
+impl Component for Events {
+    type Message = Message;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().send_future(async {
+            match get_events(0, EVENTS_SIZE).await {
+                Ok(events) => Message::EventsReceived(events),
+                Err(err) => Message::Error(err),
+            }
+        });
+        Events {
+            events: Vec::new(),
+            selected: contract_address(),
+            error: None,
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Message::EventsReceived(events) => {
+                self.events = events;
+            }
+            Message::Select(contract) => {
+                select_event(&contract);
+                self.selected = contract;
+            }
+            Message::Error(err) => {
+                web_sys::console::log_1(&format!("Events: {:?}", err).into());
+                self.error = Some(err.to_string());
+            }
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <div>
+                <h1 style="color: #FFFFFF !important;">{"Events"}</h1>
+                <table id="events" width="100%">
+                    <thead>
+                        <tr>
+                            <th>{"Event"}</th>
+                            <th>{"Organizer"}</th>
+                            <th></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {
+                            for self.events.iter().map(|event| {
+                                let contract = event.contract.clone();
+                                let onclick = ctx.link().callback(move |_| Message::Select(contract.clone()));
+                                html! {
+                                    <tr>
+                                        <td>{&event.name}</td>
+                                        <td>{&event.organizer}</td>
+                                        <td>
+                                            <button {onclick} disabled={event.contract == self.selected}>
+                                                {if event.contract == self.selected { "Selected" } else { "Select" }}
+                                            </button>
+                                        </td>
+                                    </tr>
+                                }
+                            })
+                        }
+                    </tbody>
+                </table>
+                {
+                    match (&self.error, self.events.is_empty()) {
+                        (Some(error), true) => html! {
+                            <div>{format!("Failed to fetch the events: {}", error)}</div>
+                        },
+                        (None, true) => html! {
+                            <div>{"No events yet."}</div>
+                        },
+                        _ => html! {
+                            <></>
+                        }
+                    }
+                }
+                <div>{format!("Current event: {}", self.selected)}</div>
+            </div>
+        }
+    }
+}
@@ -1,15 +1,68 @@
+use crate::azero_id::account_name;
+use crate::network_stats_component::NetworkStatsComponent;
 use crate::num_accounts_component::NumAccountsComponent;
 use crate::num_active_component::NumActiveComponent;
+use crate::services::{get_azero_id, get_leaderboard, LeaderboardEntry};
+use std::collections::HashMap;
 use yew::prelude::*;
 
-pub struct Leaderboard;
+/// Number of top networkers shown
+const LEADERBOARD_SIZE: u32 = 20;
+
+pub enum Message {
+    LeaderboardReceived(Vec<LeaderboardEntry>),
+    NameReceived(String, Option<String>),
+    Error(anyhow::Error),
+}
+
+pub struct Leaderboard {
+    entries: Vec<LeaderboardEntry>,
+    /// AZERO.ID domains by address
+    names: HashMap<String, String>,
+    error: Option<String>,
+}
 
 impl Component for Leaderboard {
-    type Message = ();
+    type Message = Message;
     type Properties = ();
 
-    fn create(_ctx: &Context<Self>) -> Self {
-        Leaderboard {}
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().send_future(async {
+            match get_leaderboard(0, LEADERBOARD_SIZE).await {
+                Ok(entries) => Message::LeaderboardReceived(entries),
+                Err(err) => Message::Error(err),
+            }
+        });
+        Leaderboard {
+            entries: Vec::new(),
+            names: HashMap::new(),
+            error: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Message::LeaderboardReceived(entries) => {
+                for entry in &entries {
+                    let address = entry.address.clone();
+                    ctx.link().send_future(async move {
+                        // accounts without a domain keep showing their address
+                        let domain = get_azero_id(address.clone()).await.ok();
+                        Message::NameReceived(address, domain)
+                    });
+                }
+                self.entries = entries;
+            }
+            Message::NameReceived(address, Some(domain)) => {
+                self.names.insert(address, domain);
+            }
+            Message::NameReceived(_, None) => return false,
+            Message::Error(err) => {
+                web_sys::console::log_1(&format!("Leaderboard: {:?}", err).into());
+                self.error = Some(err.to_string());
+            }
+        }
+        true
     }
 
     fn view(&self, _ctx: &Context<Self>) -> Html {
@@ -30,7 +83,40 @@ impl Component for Leaderboard {
                         <NumActiveComponent />
                     </div>
                 </div>
-                <img src="res/leaderboard.png" width="100%" height="100%" alt="leaderboard"/>
+                <NetworkStatsComponent />
+                <table id="leaderboard" width="100%">
+                    <thead>
+                        <tr>
+                            <th>{"#"}</th>
+                            <th>{"Networker"}</th>
+                            <th>{"Handshakes"}</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {
+                            for self.entries.iter().enumerate().map(|(rank, entry)| html! {
+                                <tr>
+                                    <td>{rank + 1}</td>
+                                    <td>{account_name(self.names.get(&entry.address), &entry.address)}</td>
+                                    <td>{entry.handshakes}</td>
+                                </tr>
+                            })
+                        }
+                    </tbody>
+                </table>
+                {
+                    match (&self.error, self.entries.is_empty()) {
+                        (Some(_), true) => html! {
+                            <div>{"Failed to fetch the leaderboard."}</div>
+                        },
+                        (None, true) => html! {
+                            <div>{"No handshakes yet."}</div>
+                        },
+                        _ => html! {
+                            <></>
+                        }
+                    }
+                }
             </div>
         }
     }
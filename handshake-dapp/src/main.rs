@@ -3,7 +3,9 @@ mod app;
 pub mod azero_id;
 pub mod connect_to;
 pub mod env;
+pub mod events;
 pub mod leaderboard;
+pub mod network_stats_component;
 pub mod num_accounts_component;
 pub mod num_active_component;
 pub mod profile;
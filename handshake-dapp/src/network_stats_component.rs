@@ -0,0 +1,108 @@
+use crate::services::{get_num_accounts, get_num_handshakes};
+use yew::prelude::*;
+
+/// Average connections per attendee and density of the handshake graph.
+pub struct NetworkStatsComponent {
+    num_accounts: Option<u32>,
+    num_handshakes: Option<u32>,
+    error: Option<String>,
+}
+
+pub enum Message {
+    Error(anyhow::Error),
+    ReceivedNumAccounts(u32),
+    ReceivedNumHandshakes(u32),
+}
+
+impl NetworkStatsComponent {
+    /// Confirmed handshakes per attendee, each handshake counting for both parties.
+    fn average_connections(&self) -> Option<f64> {
+        match (self.num_accounts?, self.num_handshakes?) {
+            (0, _) => None,
+            (accounts, handshakes) => Some(2.0 * handshakes as f64 / accounts as f64),
+        }
+    }
+
+    /// Share of all possible pairs of attendees that shook hands.
+    fn density(&self) -> Option<f64> {
+        let accounts = self.num_accounts? as f64;
+        let pairs = accounts * (accounts - 1.0) / 2.0;
+        if pairs < 1.0 {
+            return None;
+        }
+        Some(self.num_handshakes? as f64 / pairs)
+    }
+}
+
+impl Component for NetworkStatsComponent {
+    type Message = Message;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().send_future(async {
+            match get_num_accounts().await {
+                Ok(accounts) => match accounts.parse() {
+                    Ok(accounts) => Message::ReceivedNumAccounts(accounts),
+                    Err(err) => Message::Error(err.into()),
+                },
+                Err(err) => Message::Error(err),
+            }
+        });
+        ctx.link().send_future(async {
+            match get_num_handshakes().await {
+                Ok(handshakes) => match handshakes.parse() {
+                    Ok(handshakes) => Message::ReceivedNumHandshakes(handshakes),
+                    Err(err) => Message::Error(err.into()),
+                },
+                Err(err) => Message::Error(err),
+            }
+        });
+        NetworkStatsComponent {
+            num_accounts: None,
+            num_handshakes: None,
+            error: None,
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Message::ReceivedNumAccounts(count) => self.num_accounts = Some(count),
+            Message::ReceivedNumHandshakes(count) => self.num_handshakes = Some(count),
+            Message::Error(err) => {
+                web_sys::console::log_1(&format!("Network stats: {:?}", err).into());
+                self.error = Some(err.to_string());
+            }
+        }
+        true
+    }
+
+    fn view(&self, _ctx: &Context<Self>) -> Html {
+        let average = self
+            .average_connections()
+            .map(|average| format!("{average:.1}"))
+            .unwrap_or_else(|| "-".to_string());
+        let density = self
+            .density()
+            .map(|density| format!("{:.1}%", density * 100.0))
+            .unwrap_or_else(|| "-".to_string());
+        html! {
+            <>
+                <div style="display: flex; justify-content: space-between;">
+                    <div style="flex: 1; margin-right: 20px;">
+                        {"Average connections: "}
+                        <br />
+                        { average }
+                    </div>
+                    <div style="flex: 1;">
+                        {"Network density: "}
+                        <br />
+                        { density }
+                    </div>
+                </div>
+                if let Some(error) = &self.error {
+                    <div>{format!("Failed to fetch the network stats: {}", error)}</div>
+                }
+            </>
+        }
+    }
+}
@@ -21,7 +21,10 @@ impl Component for NumAccountsComponent {
             match get_num_accounts().await {
                 Ok(accounts) => {
                     web_sys::console::log_1(&format!("Num Accounts: {:?}", accounts).into());
-                    Message::ReceivedNumAccounts(accounts.parse().unwrap())
+                    match accounts.parse() {
+                        Ok(count) => Message::ReceivedNumAccounts(count),
+                        Err(_) => Message::Error(anyhow!("Invalid num accounts.")),
+                    }
                 }
                 Err(_) => Message::Error(anyhow!("Failed to fetch num accounts.".to_string())),
             }
@@ -1,15 +1,15 @@
-use crate::services::get_num_handshakes;
+use crate::services::get_num_active_accounts;
 use anyhow::anyhow;
 use yew::prelude::*;
 
 pub struct NumActiveComponent {
-    num_handshakes: Option<u128>,
+    num_active_accounts: Option<u128>,
     error: Option<String>,
 }
 
 pub enum Message {
     Error(anyhow::Error),
-    ReceivedNumAccounts(u128),
+    ReceivedNumActiveAccounts(u128),
 }
 
 impl Component for NumActiveComponent {
@@ -18,24 +18,29 @@ impl Component for NumActiveComponent {
 
     fn create(ctx: &Context<Self>) -> Self {
         ctx.link().send_future(async {
-            match get_num_handshakes().await {
-                Ok(handshakes) => {
-                    web_sys::console::log_1(&format!("Num Handshakes: {:?}", handshakes).into());
-                    Message::ReceivedNumAccounts(handshakes.parse().unwrap())
+            match get_num_active_accounts().await {
+                Ok(accounts) => {
+                    web_sys::console::log_1(&format!("Num Active Accounts: {:?}", accounts).into());
+                    match accounts.parse() {
+                        Ok(count) => Message::ReceivedNumActiveAccounts(count),
+                        Err(_) => Message::Error(anyhow!("Invalid num active accounts.")),
+                    }
+                }
+                Err(_) => {
+                    Message::Error(anyhow!("Failed to fetch num active accounts.".to_string()))
                 }
-                Err(_) => Message::Error(anyhow!("Failed to fetch num handshakes.".to_string())),
             }
         });
         NumActiveComponent {
-            num_handshakes: None,
+            num_active_accounts: None,
             error: None,
         }
     }
 
     fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
-            Message::ReceivedNumAccounts(count) => {
-                self.num_handshakes = Some(count);
+            Message::ReceivedNumActiveAccounts(count) => {
+                self.num_active_accounts = Some(count);
             }
             Message::Error(err) => self.error = Some(err.to_string()),
         }
@@ -45,7 +50,7 @@ impl Component for NumActiveComponent {
     fn view(&self, _ctx: &Context<Self>) -> Html {
         html! {
             <div>
-                { self.num_handshakes.unwrap_or_default() }
+                { self.num_active_accounts.unwrap_or_default() }
             </div>
         }
     }
@@ -1,16 +1,43 @@
 use crate::address_button_component::AddressButtonComponent;
+use crate::azero_id::account_name;
 use crate::env::URL;
+use crate::services::{
+    contract_address, get_azero_id, get_connections_of, get_contact, get_ticket_of,
+    has_contact_key, publish_contact_key, Account,
+};
 use base64::encode;
 use qrcode_generator::QrCodeEcc;
+use std::collections::HashMap;
 use yew::prelude::*;
 
+/// Number of connections shown
+const CONNECTIONS_SIZE: u32 = 50;
+
 pub enum ProfileMessage {
     AddressChanged(String),
+    AccountChanged(Account),
+    TicketReceived(Option<String>),
+    ConnectionsReceived(Vec<String>),
+    NameReceived(String, Option<String>),
+    ContactReceived(String, Option<String>),
+    ContactKeyReceived(bool),
+    PublishContactKey,
 }
 
 pub struct Profile {
     address: Option<String>,
     qr_code_data: Option<String>,
+    ticket: Option<String>,
+    /// People met at the event
+    connections: Vec<String>,
+    /// AZERO.ID domains by address
+    names: HashMap<String, String>,
+    /// Account selected in the browser extension, needed to sign
+    account: Option<Account>,
+    /// Whether others can send this browser encrypted contacts
+    contact_key_published: bool,
+    /// Contacts the connections sent, by address
+    contacts: HashMap<String, String>,
 }
 
 impl Component for Profile {
@@ -22,13 +49,19 @@ impl Component for Profile {
         Profile {
             address: None,
             qr_code_data: None,
+            ticket: None,
+            connections: Vec::new(),
+            names: HashMap::new(),
+            account: None,
+            contact_key_published: false,
+            contacts: HashMap::new(),
         }
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             ProfileMessage::AddressChanged(new_address) => {
-                self.address = Some(new_address);
+                self.address = Some(new_address.clone());
                 web_sys::console::log_1(
                     &format!(
                         "Address changed to {}",
@@ -38,28 +71,137 @@ impl Component for Profile {
                     .into(),
                 );
                 let url = format!(
-                    "{}connect?id={}",
+                    "{}connect?id={}&event={}",
                     URL,
-                    self.address.as_ref().unwrap_or(&"".to_string())
+                    self.address.as_ref().unwrap_or(&"".to_string()),
+                    contract_address()
                 );
                 let png_data: Vec<u8> =
                     qrcode_generator::to_png_to_vec(url, QrCodeEcc::Low, 1024).unwrap();
                 self.qr_code_data = Some(encode(&png_data));
 
+                let account = new_address.clone();
+                ctx.link().send_future(async move {
+                    match get_connections_of(account, 0, CONNECTIONS_SIZE).await {
+                        Ok(connections) => ProfileMessage::ConnectionsReceived(connections),
+                        Err(err) => {
+                            web_sys::console::log_1(
+                                &format!("Failed to fetch connections: {:?}", err).into(),
+                            );
+                            ProfileMessage::ConnectionsReceived(Vec::new())
+                        }
+                    }
+                });
+
+                let account = new_address.clone();
+                ctx.link().send_future(async move {
+                    match has_contact_key(account).await {
+                        Ok(published) => ProfileMessage::ContactKeyReceived(published),
+                        Err(err) => {
+                            web_sys::console::log_1(
+                                &format!("Failed to fetch contact key: {:?}", err).into(),
+                            );
+                            ProfileMessage::ContactKeyReceived(false)
+                        }
+                    }
+                });
+
+                ctx.link().send_future(async move {
+                    match get_ticket_of(new_address).await {
+                        Ok(ticket) => ProfileMessage::TicketReceived(ticket),
+                        Err(err) => {
+                            web_sys::console::log_1(
+                                &format!("Failed to fetch ticket: {:?}", err).into(),
+                            );
+                            ProfileMessage::TicketReceived(None)
+                        }
+                    }
+                });
+
+                true
+            }
+            ProfileMessage::AccountChanged(account) => {
+                self.account = Some(account);
+                false
+            }
+            ProfileMessage::TicketReceived(ticket) => {
+                self.ticket = ticket;
+                true
+            }
+            ProfileMessage::ConnectionsReceived(connections) => {
+                for connection in &connections {
+                    let address = connection.clone();
+                    ctx.link().send_future(async move {
+                        // accounts without a domain keep showing their address
+                        let domain = get_azero_id(address.clone()).await.ok();
+                        ProfileMessage::NameReceived(address, domain)
+                    });
+                    if let Some(own_address) = self.address.clone() {
+                        let address = connection.clone();
+                        ctx.link().send_future(async move {
+                            let contact = get_contact(address.clone(), own_address).await.ok();
+                            ProfileMessage::ContactReceived(address, contact.flatten())
+                        });
+                    }
+                }
+                self.connections = connections;
+                true
+            }
+            ProfileMessage::NameReceived(address, Some(domain)) => {
+                self.names.insert(address, domain);
+                true
+            }
+            ProfileMessage::NameReceived(_, None) => false,
+            ProfileMessage::ContactReceived(address, Some(contact)) => {
+                self.contacts.insert(address, contact);
+                true
+            }
+            ProfileMessage::ContactReceived(_, None) => false,
+            ProfileMessage::ContactKeyReceived(published) => {
+                self.contact_key_published = published;
                 true
             }
+            ProfileMessage::PublishContactKey => {
+                if let Some(account) = self.account.clone() {
+                    ctx.link().send_future(async move {
+                        let published = publish_contact_key(account.source, account.address)
+                            .await
+                            .map_err(|err| {
+                                web_sys::console::log_1(
+                                    &format!("Failed to publish contact key: {:?}", err).into(),
+                                );
+                            })
+                            .is_ok();
+                        ProfileMessage::ContactKeyReceived(published)
+                    });
+                }
+                false
+            }
         }
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
         html! {
             <div id="profile">
-                <AddressButtonComponent on_address={ctx.link().callback(ProfileMessage::AddressChanged)} />
+                <AddressButtonComponent on_address={ctx.link().callback(ProfileMessage::AddressChanged)} on_account={Some(ctx.link().callback(ProfileMessage::AccountChanged))} />
                 <img id="currentevent" src="res/currentevent.png" width="100%" height="100%" alt="currentevent"/>
+                {
+                    match (&self.address, &self.ticket) {
+                        (Some(_), Some(ticket)) => html! {
+                            <div id="ticket">{format!("Ticket #{}", ticket)}</div>
+                        },
+                        (Some(_), None) => html! {
+                            <div id="ticket">{"No ticket for this event yet"}</div>
+                        },
+                        (None, _) => html! {
+                            <></>
+                        }
+                    }
+                }
                 {
                     match &self.qr_code_data {
                         Some(qr_code_data) => html! {
-                            <a href={format!("{}connect?id={}", URL, self.address.as_ref().unwrap_or(&"".to_string()))} target="blank">
+                            <a href={format!("{}connect?id={}&event={}", URL, self.address.as_ref().unwrap_or(&"".to_string()), contract_address())} target="blank">
                                 <img width=320 height=320 src={format!("data:image/png;base64,{}", qr_code_data)} />
                             </a>
                         },
@@ -68,7 +210,45 @@ impl Component for Profile {
                         }
                     }
                 }
-                <img src="res/connections.png" width="100%" height="100%" alt="connections"/>
+                {
+                    match (&self.ticket, self.contact_key_published) {
+                        (Some(_), false) => html! {
+                            <button id="contact-key" onclick={ctx.link().callback(|_| ProfileMessage::PublishContactKey)} disabled={self.account.is_none()}>
+                                {"Receive contacts on this device"}
+                            </button>
+                        },
+                        _ => html! {
+                            <></>
+                        }
+                    }
+                }
+                {
+                    match (&self.address, self.connections.is_empty()) {
+                        (Some(_), false) => html! {
+                            <div id="connections">
+                                <h2>{"People I met"}</h2>
+                                <ul>
+                                    {
+                                        for self.connections.iter().map(|address| html! {
+                                            <li>
+                                                {account_name(self.names.get(address), address)}
+                                                if let Some(contact) = self.contacts.get(address) {
+                                                    {format!(" – {}", contact)}
+                                                }
+                                            </li>
+                                        })
+                                    }
+                                </ul>
+                            </div>
+                        },
+                        (Some(_), true) => html! {
+                            <div id="connections">{"No handshakes yet"}</div>
+                        },
+                        (None, _) => html! {
+                            <></>
+                        }
+                    }
+                }
             </div>
         }
     }
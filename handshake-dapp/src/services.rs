@@ -1,4 +1,4 @@
-use crate::env::CONTRACT;
+use crate::env::{CONTRACT, REGISTRY};
 use anyhow::anyhow;
 use js_sys::Promise;
 use serde::{Deserialize, Serialize};
@@ -13,6 +13,21 @@ extern "C" {
     pub fn js_fetch_num_accounts(contract: String) -> Promise;
     #[wasm_bindgen(js_name = fetchNumHandshakes)]
     pub fn js_fetch_num_handshakes(contract: String) -> Promise;
+    #[wasm_bindgen(js_name = fetchNumActiveAccounts)]
+    pub fn js_fetch_num_active_accounts(contract: String) -> Promise;
+    #[wasm_bindgen(js_name = fetchTicketOf)]
+    pub fn js_fetch_ticket_of(contract: String, account: String) -> Promise;
+    #[wasm_bindgen(js_name = fetchConnectionsOf)]
+    pub fn js_fetch_connections_of(
+        contract: String,
+        account: String,
+        offset: u32,
+        limit: u32,
+    ) -> Promise;
+    #[wasm_bindgen(js_name = fetchLeaderboard)]
+    pub fn js_fetch_leaderboard(contract: String, offset: u32, limit: u32) -> Promise;
+    #[wasm_bindgen(js_name = fetchEvents)]
+    pub fn js_fetch_events(registry: String, offset: u32, limit: u32) -> Promise;
 
     #[wasm_bindgen(js_name = doHandshake)]
     pub fn js_handshake(
@@ -20,7 +35,15 @@ extern "C" {
         source: String,
         sender_address: String,
         destination_address: String,
+        note: String,
+        contact: String,
     ) -> Promise;
+    #[wasm_bindgen(js_name = hasContactKey)]
+    pub fn js_has_contact_key(contract: String, account: String) -> Promise;
+    #[wasm_bindgen(js_name = publishContactKey)]
+    pub fn js_publish_contact_key(contract: String, source: String, account: String) -> Promise;
+    #[wasm_bindgen(js_name = fetchContact)]
+    pub fn js_fetch_contact(contract: String, from: String, to: String) -> Promise;
     #[wasm_bindgen(js_name = doAccountLookup)]
     pub fn js_do_account_lookup(accountAddress: String) -> Promise;
 }
@@ -38,6 +61,68 @@ pub struct Account {
     pub address: String,
 }
 
+/// Entry of the leaderboard
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    /// ss58 formatted address of the attendee
+    pub address: String,
+    /// number of confirmed handshakes
+    pub handshakes: u32,
+}
+
+/// Event created in the registry
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EventEntry {
+    /// ss58 formatted address of the event's Handshake contract
+    pub contract: String,
+    /// ss58 formatted address of the organizer
+    pub organizer: String,
+    /// name of the event
+    pub name: String,
+}
+
+/// Key of the selected event in the browser's local storage
+const EVENT_KEY: &str = "handshake-event";
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+/// Returns the address of the selected event, [`CONTRACT`] if none was selected.
+pub fn contract_address() -> String {
+    local_storage()
+        .and_then(|storage| storage.get_item(EVENT_KEY).ok()?)
+        .unwrap_or_else(|| CONTRACT.to_string())
+}
+
+/// Selects the event at `contract` for all further calls.
+pub fn select_event(contract: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.set_item(EVENT_KEY, contract);
+    }
+}
+
+/// Whether `contract` is an event of the registry, or [`CONTRACT`] without a registry.
+pub async fn is_known_event(contract: &str) -> Result<bool, anyhow::Error> {
+    if REGISTRY.is_none() {
+        return Ok(contract == CONTRACT);
+    }
+    let mut offset = 0;
+    loop {
+        let events = get_events(offset, EVENTS_PAGE_SIZE).await?;
+        if events.iter().any(|event| event.contract == contract) {
+            return Ok(true);
+        }
+        if events.len() < EVENTS_PAGE_SIZE as usize {
+            return Ok(false);
+        }
+        offset += EVENTS_PAGE_SIZE;
+    }
+}
+
+/// Number of events fetched at once when looking for one
+const EVENTS_PAGE_SIZE: u32 = 50;
+
 pub async fn get_accounts() -> Result<Vec<Account>, anyhow::Error> {
     let result = JsFuture::from(js_get_accounts())
         .await
@@ -60,7 +145,7 @@ pub async fn get_azero_id(account: String) -> Result<String, anyhow::Error> {
 }
 
 pub async fn get_num_accounts() -> Result<String, anyhow::Error> {
-    let result = JsFuture::from(js_fetch_num_accounts(CONTRACT.to_string()))
+    let result = JsFuture::from(js_fetch_num_accounts(contract_address()))
         .await
         .map_err(|js_err| anyhow!("{js_err:?}"))?;
     let num_accounts = result
@@ -70,7 +155,7 @@ pub async fn get_num_accounts() -> Result<String, anyhow::Error> {
 }
 
 pub async fn get_num_handshakes() -> Result<String, anyhow::Error> {
-    let result = JsFuture::from(js_fetch_num_handshakes(CONTRACT.to_string()))
+    let result = JsFuture::from(js_fetch_num_handshakes(contract_address()))
         .await
         .map_err(|js_err| anyhow!("{js_err:?}"))?;
     let num_handshakes = result
@@ -79,16 +164,81 @@ pub async fn get_num_handshakes() -> Result<String, anyhow::Error> {
     Ok(num_handshakes)
 }
 
+/// Returns the number of accounts with at least one confirmed handshake.
+pub async fn get_num_active_accounts() -> Result<String, anyhow::Error> {
+    let result = JsFuture::from(js_fetch_num_active_accounts(contract_address()))
+        .await
+        .map_err(|js_err| anyhow!("{js_err:?}"))?;
+    let num_active_accounts = result
+        .as_string()
+        .ok_or(anyhow!("Expected a stringified JSON"))?;
+    Ok(num_active_accounts)
+}
+
+/// Returns the ticket id of `account`, `None` if it did not sign up.
+pub async fn get_ticket_of(account: String) -> Result<Option<String>, anyhow::Error> {
+    let result = JsFuture::from(js_fetch_ticket_of(contract_address(), account))
+        .await
+        .map_err(|js_err| anyhow!("{js_err:?}"))?;
+    let ticket = result
+        .as_string()
+        .ok_or(anyhow!("Error converting JsValue into String"))?;
+    Ok(Some(ticket).filter(|ticket| !ticket.is_empty()))
+}
+
+/// Returns `limit` entries of the leaderboard starting at rank `offset`.
+pub async fn get_leaderboard(
+    offset: u32,
+    limit: u32,
+) -> Result<Vec<LeaderboardEntry>, anyhow::Error> {
+    let result = JsFuture::from(js_fetch_leaderboard(contract_address(), offset, limit))
+        .await
+        .map_err(|js_err| anyhow!("{js_err:?}"))?;
+    let entries_str = result
+        .as_string()
+        .ok_or(anyhow!("Expected a stringified JSON"))?;
+    let entries: Vec<LeaderboardEntry> = serde_json::from_str(&entries_str)?;
+    Ok(entries)
+}
+
+/// Returns the addresses `account` shares a confirmed handshake with,
+/// `limit` entries starting at `offset`.
+pub async fn get_connections_of(
+    account: String,
+    offset: u32,
+    limit: u32,
+) -> Result<Vec<String>, anyhow::Error> {
+    let result = JsFuture::from(js_fetch_connections_of(
+        contract_address(),
+        account,
+        offset,
+        limit,
+    ))
+    .await
+    .map_err(|js_err| anyhow!("{js_err:?}"))?;
+    let connections_str = result
+        .as_string()
+        .ok_or(anyhow!("Expected a stringified JSON"))?;
+    let connections: Vec<String> = serde_json::from_str(&connections_str)?;
+    Ok(connections)
+}
+
+/// Sends a handshake to `destination_address`, attaching `note` and `contact`
+/// unless they are empty. The contact is encrypted to the destination's contact key.
 pub async fn do_handshake(
     source: String,
     sender_address: String,
     destination_address: String,
+    note: String,
+    contact: String,
 ) -> Result<String, anyhow::Error> {
     let result = JsFuture::from(js_handshake(
-        CONTRACT.to_string(),
+        contract_address(),
         source,
         sender_address,
         destination_address,
+        note,
+        contact,
     ))
     .await
     .map_err(|js_err| anyhow!("{js_err:?}"))?;
@@ -97,3 +247,49 @@ pub async fn do_handshake(
         .ok_or(anyhow!("Error converting JsValue into String"))?;
     Ok(result)
 }
+
+/// Returns `limit` events of the registry starting at `offset`.
+pub async fn get_events(offset: u32, limit: u32) -> Result<Vec<EventEntry>, anyhow::Error> {
+    let registry = REGISTRY.ok_or(anyhow!("No registry deployed"))?;
+    let result = JsFuture::from(js_fetch_events(registry.to_string(), offset, limit))
+        .await
+        .map_err(|js_err| anyhow!("{js_err:?}"))?;
+    let events_str = result
+        .as_string()
+        .ok_or(anyhow!("Expected a stringified JSON"))?;
+    let events: Vec<EventEntry> = serde_json::from_str(&events_str)?;
+    Ok(events)
+}
+
+/// Whether `account` published the contact key kept in this browser.
+pub async fn has_contact_key(account: String) -> Result<bool, anyhow::Error> {
+    let result = JsFuture::from(js_has_contact_key(contract_address(), account))
+        .await
+        .map_err(|js_err| anyhow!("{js_err:?}"))?;
+    result
+        .as_bool()
+        .ok_or(anyhow!("Error converting JsValue into bool"))
+}
+
+/// Publishes the contact key of this browser for `account`, so others can
+/// send it their contact.
+pub async fn publish_contact_key(source: String, account: String) -> Result<String, anyhow::Error> {
+    let result = JsFuture::from(js_publish_contact_key(contract_address(), source, account))
+        .await
+        .map_err(|js_err| anyhow!("{js_err:?}"))?;
+    let result = result
+        .as_string()
+        .ok_or(anyhow!("Error converting JsValue into String"))?;
+    Ok(result)
+}
+
+/// Returns the contact `from` sent to `to`, `None` if there is none this browser can read.
+pub async fn get_contact(from: String, to: String) -> Result<Option<String>, anyhow::Error> {
+    let result = JsFuture::from(js_fetch_contact(contract_address(), from, to))
+        .await
+        .map_err(|js_err| anyhow!("{js_err:?}"))?;
+    let contact = result
+        .as_string()
+        .ok_or(anyhow!("Error converting JsValue into String"))?;
+    Ok(Some(contact).filter(|contact| !contact.is_empty()))
+}
@@ -0,0 +1,135 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// PSP22 token with the selectors of the standard, minting its supply to the deployer.
+#[ink::contract]
+pub mod psp22_mock {
+    use ink::prelude::string::String;
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum PSP22Error {
+        Custom(String),
+        InsufficientBalance,
+        InsufficientAllowance,
+        ZeroRecipientAddress,
+        ZeroSenderAddress,
+        SafeTransferCheckFailed(String),
+    }
+
+    #[ink(storage)]
+    pub struct Psp22Mock {
+        total_supply: Balance,
+        balances: Mapping<AccountId, Balance>,
+        allowances: Mapping<(AccountId, AccountId), Balance>,
+    }
+
+    impl Psp22Mock {
+        #[ink(constructor)]
+        pub fn new(total_supply: Balance) -> Self {
+            let mut balances = Mapping::default();
+            balances.insert(Self::env().caller(), &total_supply);
+            Self {
+                total_supply,
+                balances,
+                allowances: Mapping::default(),
+            }
+        }
+
+        #[ink(message, selector = 0x162df8c2)]
+        pub fn total_supply(&self) -> Balance {
+            self.total_supply
+        }
+
+        #[ink(message, selector = 0x6568382f)]
+        pub fn balance_of(&self, owner: AccountId) -> Balance {
+            self.balances.get(owner).unwrap_or_default()
+        }
+
+        #[ink(message, selector = 0x4d47d921)]
+        pub fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowances.get((owner, spender)).unwrap_or_default()
+        }
+
+        #[ink(message, selector = 0xdb20f9f5)]
+        pub fn transfer(
+            &mut self,
+            to: AccountId,
+            value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            self.move_balance(self.env().caller(), to, value)
+        }
+
+        #[ink(message, selector = 0x54b3c76e)]
+        pub fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let spender = self.env().caller();
+            let allowance = self.allowance(from, spender);
+            if allowance < value {
+                return Err(PSP22Error::InsufficientAllowance);
+            }
+            self.move_balance(from, to, value)?;
+            self.allowances
+                .insert((from, spender), &(allowance - value));
+            Ok(())
+        }
+
+        #[ink(message, selector = 0xb20f1bbd)]
+        pub fn approve(&mut self, spender: AccountId, value: Balance) -> Result<(), PSP22Error> {
+            self.allowances
+                .insert((self.env().caller(), spender), &value);
+            Ok(())
+        }
+
+        fn move_balance(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> Result<(), PSP22Error> {
+            let from_balance = self.balance_of(from);
+            if from_balance < value {
+                return Err(PSP22Error::InsufficientBalance);
+            }
+            self.balances.insert(from, &(from_balance - value));
+            let to_balance = self.balance_of(to);
+            self.balances.insert(to, &(to_balance + value));
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[ink::test]
+        fn transfer_from_needs_allowance() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = Psp22Mock::new(100);
+            assert_eq!(token.balance_of(accounts.alice), 100);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                token.transfer_from(accounts.alice, accounts.bob, 10, Vec::new()),
+                Err(PSP22Error::InsufficientAllowance)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(token.approve(accounts.bob, 10), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                token.transfer_from(accounts.alice, accounts.bob, 10, Vec::new()),
+                Ok(())
+            );
+            assert_eq!(token.balance_of(accounts.bob), 10);
+            assert_eq!(token.allowance(accounts.alice, accounts.bob), 0);
+        }
+    }
+}
@@ -0,0 +1,221 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+pub mod registry {
+    use handshake::handshake::{HandshakeRef, MAX_PAGE_SIZE};
+    use ink::codegen::EmitEvent;
+    use ink::prelude::string::String;
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+    use ink::ToAccountId;
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum RegistryError {
+        /// Instantiating the event contract failed.
+        InstantiationFailed,
+    }
+
+    /// An event created by the registry.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct EventInfo {
+        /// Address of the Handshake contract of the event.
+        pub contract: AccountId,
+        pub organizer: AccountId,
+        pub name: String,
+    }
+
+    /// Emitted when an organizer creates an event.
+    #[ink(event)]
+    pub struct EventCreated {
+        #[ink(topic)]
+        contract: AccountId,
+        #[ink(topic)]
+        organizer: AccountId,
+        name: String,
+    }
+
+    #[ink(storage)]
+    pub struct Registry {
+        /// Code hash of the uploaded Handshake contract.
+        handshake_code_hash: Hash,
+        /// Created events in creation order.
+        events: Mapping<u32, EventInfo>,
+        num_events: u32,
+    }
+
+    impl Registry {
+        #[ink(constructor)]
+        pub fn new(handshake_code_hash: Hash) -> Self {
+            Self {
+                handshake_code_hash,
+                events: Mapping::default(),
+                num_events: 0,
+            }
+        }
+
+        /// Instantiates a new Handshake event owned by the caller, staking in the
+        /// PSP22 `token` if given.
+        #[ink(message)]
+        pub fn create_event(
+            &mut self,
+            ticket_price: Balance,
+            name: String,
+            symbol: String,
+            token: Option<AccountId>,
+        ) -> Result<AccountId, RegistryError> {
+            let organizer = self.env().caller();
+            let contract =
+                HandshakeRef::with_owner(organizer, ticket_price, name.clone(), symbol, token)
+                    .code_hash(self.handshake_code_hash)
+                    .endowment(0)
+                    .salt_bytes(self.num_events.to_le_bytes())
+                    .try_instantiate()
+                    .map_err(|_| RegistryError::InstantiationFailed)?
+                    .map_err(|_| RegistryError::InstantiationFailed)?
+                    .to_account_id();
+
+            self.events.insert(
+                self.num_events,
+                &EventInfo {
+                    contract,
+                    organizer,
+                    name: name.clone(),
+                },
+            );
+            self.num_events += 1;
+            // fully qualified since the Handshake dependency implements `EmitEvent` too
+            EmitEvent::<Registry>::emit_event(
+                self.env(),
+                EventCreated {
+                    contract,
+                    organizer,
+                    name,
+                },
+            );
+            Ok(contract)
+        }
+
+        #[ink(message)]
+        pub fn num_events(&self) -> u32 {
+            self.num_events
+        }
+
+        #[ink(message)]
+        pub fn event(&self, index: u32) -> Option<EventInfo> {
+            self.events.get(index)
+        }
+
+        /// Created events, `limit` entries starting at `offset`.
+        #[ink(message)]
+        pub fn events(&self, offset: u32, limit: u32) -> Vec<EventInfo> {
+            let end = offset
+                .saturating_add(limit.min(MAX_PAGE_SIZE))
+                .min(self.num_events);
+            (offset..end)
+                .filter_map(|index| self.events.get(index))
+                .collect()
+        }
+
+        #[ink(message)]
+        pub fn handshake_code_hash(&self) -> Hash {
+            self.handshake_code_hash
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[ink::test]
+        fn new_registry_is_empty() {
+            let registry = Registry::new(Hash::from([0x01; 32]));
+            assert_eq!(registry.num_events(), 0);
+            assert_eq!(registry.events(0, 10), vec![]);
+            assert_eq!(registry.event(0), None);
+            assert_eq!(registry.handshake_code_hash(), Hash::from([0x01; 32]));
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    pub mod e2e_tests {
+        use super::*;
+        use handshake::handshake::Phase;
+        use ink::primitives::AccountId;
+        use ink_e2e::subxt::tx::Signer;
+        use ink_e2e::subxt::utils::AccountId32;
+        use ink_e2e::{build_message, Keypair, PolkadotConfig};
+
+        type E2EResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+        fn address_of(keypair: &Keypair) -> AccountId {
+            let address: AccountId32 = <Keypair as Signer<PolkadotConfig>>::account_id(keypair);
+            address.0.into()
+        }
+
+        #[ink_e2e::test(additional_contracts = "../contract/Cargo.toml")]
+        async fn create_and_list_events(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let code_hash = client
+                .upload("handshake", &ink_e2e::alice(), None)
+                .await
+                .expect("upload failed")
+                .code_hash;
+
+            let constructor = RegistryRef::new(code_hash);
+            let address = client
+                .instantiate(
+                    "handshake_registry",
+                    &ink_e2e::alice(),
+                    constructor,
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            let mut contracts = Vec::new();
+            for name in ["Meetup #1", "Meetup #2"] {
+                let _msg = build_message::<RegistryRef>(address.clone()).call(|registry| {
+                    registry.create_event(7, String::from(name), String::from("HSK"), None)
+                });
+                let result = client
+                    .call(&ink_e2e::bob(), _msg, 0, None)
+                    .await
+                    .expect("create event failed");
+                contracts.push(result.return_value().expect("event not created"));
+            }
+            assert_ne!(contracts[0], contracts[1]);
+
+            let events = {
+                let _msg = build_message::<RegistryRef>(address.clone())
+                    .call(|registry| registry.events(0, 10));
+                client
+                    .call_dry_run(&ink_e2e::alice(), &_msg, 0, None)
+                    .await
+                    .return_value()
+            };
+            assert_eq!(events.len(), 2);
+            assert_eq!(events[1].name, String::from("Meetup #2"));
+            assert_eq!(events[1].organizer, address_of(&ink_e2e::bob()));
+
+            // the organizer owns the created event
+            let phase = {
+                let _msg = build_message::<HandshakeRef>(contracts[0].clone())
+                    .call(|contract| contract.advance_phase());
+                client
+                    .call(&ink_e2e::bob(), _msg, 0, None)
+                    .await
+                    .expect("advance phase failed")
+                    .return_value()
+            };
+            assert_eq!(phase, Ok(Phase::Networking));
+
+            Ok(())
+        }
+    }
+}